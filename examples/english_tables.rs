@@ -0,0 +1,124 @@
+//! Counts the English tables of the `data` directory from the text files of a corpus:
+//!
+//! ```sh
+//! cargo run --release --example english_tables -- data/corpus data
+//! ```
+//!
+//! The letters are counted across the words, the words are the runs of letters with the apostrophes
+//! removed, so "don't" is counted as DONT. Any other corpus of plain text files can be used the same way.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+/// The quadgrams kept in the table, the most common first (all the shorter n-grams are kept)
+const QUADGRAMS: usize = 20000;
+/// How many times a word has to be seen to be kept as a common word
+const COMMON_WORD_COUNT: usize = 3;
+/// The shortest words counted for `segment`, the shorter ones are mostly initials and numerals
+const SEGMENTED_WORD_LENGTH: usize = 3;
+/// The words shorter than that which are counted
+const SHORT_WORDS: &[&str] = &[
+    "a", "i", "am", "an", "as", "at", "be", "by", "do", "go", "he", "hi", "if", "in", "is", "it", "me", "my", "no", "of",
+    "oh", "ok", "on", "or", "so", "to", "up", "us", "we",
+];
+/// How many times a word pair has to be seen to be kept
+const PAIR_COUNT: usize = 2;
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.len() != 2 {
+        eprintln!("Usage: english_tables <corpus directory> <output directory>");
+        process::exit(2);
+    }
+    if let Err(error) = run(Path::new(&args[0]), Path::new(&args[1])) {
+        eprintln!("Error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run(corpus: &Path, output: &Path) -> Result<(), String> {
+    let text = read_corpus(corpus)?;
+    let letters = text
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<Vec<char>>();
+    for (n, name) in [(1, "unigrams"), (2, "bigrams"), (3, "trigrams"), (4, "quadgrams")].iter().copied() {
+        let ngrams = most_common(count(letters.windows(n).map(|ngram| ngram.iter().collect())));
+        let kept = if n == 4 { QUADGRAMS } else { ngrams.len() };
+        write(&output.join(format!("english_{}.txt", name)), ngrams.iter().take(kept))?;
+    }
+
+    let counts = count(words(&text));
+    let words = most_common(counts.clone());
+    let common = words.iter().filter(|(_, count)| *count >= COMMON_WORD_COUNT).map(|(word, _)| word.clone());
+    fs::write(output.join("english_words.txt"), common.map(|word| word + "\n").collect::<String>())
+        .map_err(|error| format!("can't write the common words: {}", error))?;
+
+    write_word_counts(&text, counts, output)
+}
+
+/// The words of the corpus with their counts, and the pairs of these words seen in the same sentence
+fn write_word_counts(text: &str, counts: HashMap<String, usize>, output: &Path) -> Result<(), String> {
+    let known = counts
+        .into_iter()
+        .filter(|(word, _)| word.len() >= SEGMENTED_WORD_LENGTH || SHORT_WORDS.contains(&word.to_ascii_lowercase().as_str()))
+        .collect::<HashMap<String, usize>>();
+    let pairs = text
+        .split(|c| ".!?;:\n".contains(c))
+        .flat_map(|sentence| {
+            let words = words(sentence).collect::<Vec<String>>();
+            words.windows(2).map(|pair| pair.join(" ")).collect::<Vec<String>>()
+        })
+        .filter(|pair| pair.split(' ').all(|word| known.contains_key(word)));
+    let pairs = most_common(count(pairs));
+    write(&output.join("english_word_counts.txt"), most_common(known).iter())?;
+    write(&output.join("english_word_bigrams.txt"), pairs.iter().filter(|(_, count)| *count >= PAIR_COUNT))
+}
+
+/// The text of all the `.txt` files of the directory, in the order of their names
+fn read_corpus(corpus: &Path) -> Result<String, String> {
+    let entries = fs::read_dir(corpus).map_err(|error| format!("can't read {}: {}", corpus.display(), error))?;
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut text = String::new();
+    for path in paths {
+        text += &fs::read_to_string(&path).map_err(|error| format!("can't read {}: {}", path.display(), error))?;
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// The uppercase words of the text
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_ascii_alphabetic() && c != '\'')
+        .map(|word| word.chars().filter(|c| c.is_ascii_alphabetic()).collect::<String>().to_ascii_uppercase())
+        .filter(|word| !word.is_empty())
+}
+
+fn count(items: impl Iterator<Item = String>) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The most common first, the ties in alphabetical order so the tables don't change between runs
+fn most_common(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts = counts.into_iter().collect::<Vec<(String, usize)>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// One "TOKEN count" line by token
+fn write<'a>(path: &Path, counts: impl Iterator<Item = &'a (String, usize)>) -> Result<(), String> {
+    let lines = counts.map(|(token, count)| format!("{} {}\n", token, count)).collect::<String>();
+    fs::write(path, lines).map_err(|error| format!("can't write {}: {}", path.display(), error))
+}
@@ -0,0 +1,108 @@
+use crate::alphabet::Alphabet;
+
+// Letter frequencies (in percents) of the languages, in the order of their alphabets.
+// The values are approximate and only meant for frequency analysis.
+
+const ENGLISH: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+const RUSSIAN: &str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+const RUSSIAN_FREQUENCIES: [f64; 33] = [
+    8.01, 1.59, 4.54, 1.70, 2.98, 8.45, 0.04, 0.94, 1.65, 7.35, 1.21, 3.49, 4.40, 3.21, 6.70,
+    10.97, 2.81, 4.73, 5.47, 6.26, 2.62, 0.26, 0.97, 0.48, 1.44, 0.73, 0.36, 0.04, 1.90, 1.74,
+    0.32, 0.64, 2.01,
+];
+
+const ARMENIAN: &str = "ԱԲԳԴԵԶԷԸԹԺԻԼԽԾԿՀՁՂՃՄՅՆՇՈՉՊՋՌՍՎՏՐՑՒՓՔՕՖ";
+const ARMENIAN_FREQUENCIES: [f64; 38] = [
+    9.5, 1.1, 1.2, 1.6, 8.4, 0.7, 1.4, 2.9, 0.9, 0.3, 8.2, 4.3, 0.8, 0.8, 3.6, 2.6, 0.5, 0.7,
+    0.4, 4.1, 2.9, 9.0, 1.0, 6.8, 0.7, 1.3, 0.4, 0.5, 4.6, 2.9, 2.8, 6.4, 1.0, 4.2, 0.3, 1.2,
+    0.3, 0.1,
+];
+
+const GREEK: &str = "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ";
+const GREEK_FREQUENCIES: [f64; 24] = [
+    12.0, 0.8, 1.8, 1.8, 8.0, 0.4, 4.6, 1.3, 8.6, 4.1, 2.7, 3.4, 6.4, 0.4, 9.8, 4.1, 4.4, 7.9,
+    8.6, 4.1, 0.8, 1.2, 0.1, 1.8,
+];
+// Letters with tonos and dialytika are the same letters for the cipher
+const GREEK_EQUIVALENTS: [(char, char); 11] = [
+    ('Ά', 'Α'),
+    ('Έ', 'Ε'),
+    ('Ή', 'Η'),
+    ('Ί', 'Ι'),
+    ('Ϊ', 'Ι'),
+    ('ΐ', 'Ι'),
+    ('Ό', 'Ο'),
+    ('Ύ', 'Υ'),
+    ('Ϋ', 'Υ'),
+    ('ΰ', 'Υ'),
+    ('Ώ', 'Ω'),
+];
+
+/// All the built-in alphabets use the one-based indexing from the book
+impl Alphabet {
+    /// A..Z, 1 is A, 0 is Z
+    pub fn english() -> Self {
+        Self::built_in(ENGLISH, &ENGLISH_FREQUENCIES)
+    }
+
+    /// The 33-letter Russian alphabet, Ё is a separate letter
+    pub fn russian() -> Self {
+        Self::built_in(RUSSIAN, &RUSSIAN_FREQUENCIES)
+    }
+
+    /// The 38-letter Armenian alphabet, the ligature և is treated as ԵՒ
+    pub fn armenian() -> Self {
+        Self::built_in(ARMENIAN, &ARMENIAN_FREQUENCIES)
+    }
+
+    /// The 24-letter Greek alphabet, accented letters are treated as their base letters
+    pub fn greek() -> Self {
+        Self::built_in(GREEK, &GREEK_FREQUENCIES).with_equivalents(&GREEK_EQUIVALENTS)
+    }
+
+    fn built_in(symbols: &str, frequencies: &[f64]) -> Self {
+        Self::new(symbols)
+            .unwrap()
+            .with_frequencies(frequencies.to_vec())
+            .unwrap()
+            .one_based(true)
+    }
+}
+
+#[cfg(test)]
+mod languages_test {
+    use crate::alphabet::Alphabet;
+
+    #[test]
+    fn built_in_alphabets_have_frequencies_for_every_letter() {
+        for (alphabet, modulus) in [
+            (Alphabet::english(), 26),
+            (Alphabet::russian(), 33),
+            (Alphabet::armenian(), 38),
+            (Alphabet::greek(), 24),
+        ] {
+            assert_eq!(alphabet.modulus(), modulus);
+            let total: f64 = alphabet.frequencies().unwrap().iter().sum();
+            assert!((total - 100.0).abs() < 1.5, "{}", total);
+        }
+    }
+
+    #[test]
+    fn most_frequent_symbols_are_sorted() {
+        assert_eq!(&Alphabet::english().most_frequent_symbols()[..3], &['E', 'T', 'A']);
+        assert_eq!(Alphabet::russian().most_frequent_symbols()[0], 'О');
+        assert_eq!(Alphabet::greek().most_frequent_symbols()[0], 'Α');
+    }
+
+    #[test]
+    fn non_latin_letters_are_normalized() {
+        assert_eq!(Alphabet::russian().normalize("Ёлка, ель!"), "ЁЛКАЕЛЬ".to_owned());
+        assert_eq!(Alphabet::armenian().normalize("Երևան"), "ԵՐԵՒԱՆ".to_owned());
+        assert_eq!(Alphabet::greek().normalize("Καλημέρα, κόσμε"), "ΚΑΛΗΜΕΡΑΚΟΣΜΕ".to_owned());
+    }
+}
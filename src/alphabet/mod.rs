@@ -0,0 +1,224 @@
+use crate::error::CipherError;
+
+mod languages;
+
+/// Ordered set of symbols the ciphers work with, the modulus of all the calculations is its length.
+///
+/// Indexing is zero-based by default (first symbol is 0), but can be switched to the one-based
+/// indexing used in the book, where the first symbol is 1 and the last one is 0 (1 is A, 0 is Z).
+///
+/// Alphabets can also carry the letter frequencies of their language, which the hacks use for
+/// frequency analysis. Built-in alphabets: English, Russian, Armenian and Greek.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+    one_based: bool,
+    frequencies: Option<Vec<f64>>,
+    equivalents: Vec<(char, char)>,
+}
+
+impl Alphabet {
+    pub fn new(symbols: &str) -> Result<Self, CipherError> {
+        let symbols = symbols.chars().collect::<Vec<char>>();
+        if symbols.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        if let Some(i) = (1..symbols.len()).find(|i| symbols[..*i].contains(&symbols[*i])) {
+            return Err(CipherError::InvalidAlphabet(format!("'{}' is repeated", symbols[i])));
+        }
+        Ok(Alphabet {
+            symbols,
+            one_based: false,
+            frequencies: None,
+            equivalents: vec![],
+        })
+    }
+
+    /// Relative frequencies of the symbols in the language, in the order of the symbols
+    pub fn with_frequencies(mut self, frequencies: Vec<f64>) -> Result<Self, CipherError> {
+        if frequencies.len() != self.symbols.len() {
+            return Err(CipherError::InvalidAlphabet(format!(
+                "{} frequencies given for {} symbols",
+                frequencies.len(),
+                self.symbols.len()
+            )));
+        }
+        if let Some(frequency) = frequencies.iter().find(|x| !x.is_finite() || **x < 0.0) {
+            return Err(CipherError::InvalidAlphabet(format!("{} is not a frequency", frequency)));
+        }
+        self.frequencies = Some(frequencies);
+        Ok(self)
+    }
+
+    /// Symbols that should be treated as another symbol of the alphabet, like accented letters
+    pub fn with_equivalents(mut self, equivalents: &[(char, char)]) -> Self {
+        self.equivalents.extend_from_slice(equivalents);
+        self
+    }
+
+    pub fn one_based(mut self, one_based: bool) -> Self {
+        self.one_based = one_based;
+        self
+    }
+
+    pub fn is_one_based(&self) -> bool {
+        self.one_based
+    }
+
+    pub fn modulus(&self) -> u32 {
+        self.symbols.len() as u32
+    }
+
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    pub fn frequencies(&self) -> Option<&[f64]> {
+        self.frequencies.as_deref()
+    }
+
+    /// The symbols sorted from the most frequent to the least frequent one,
+    /// empty if the alphabet has no frequencies
+    pub fn most_frequent_symbols(&self) -> Vec<char> {
+        let mut symbols = match &self.frequencies {
+            Some(frequencies) => self.symbols.iter().cloned().zip(frequencies.iter().cloned()).collect(),
+            None => vec![],
+        };
+        symbols.sort_by(|a: &(char, f64), b| b.1.total_cmp(&a.1));
+        symbols.into_iter().map(|x| x.0).collect()
+    }
+
+    pub fn contains(&self, symbol: char) -> bool {
+        self.index_of(symbol).is_some()
+    }
+
+    /// The index of the symbol, letters are matched case-insensitively
+    pub fn index_of(&self, symbol: char) -> Option<u32> {
+        self.position(symbol)
+            .or_else(|| Self::single(symbol.to_uppercase()).and_then(|s| self.position(s)))
+            .or_else(|| Self::single(symbol.to_lowercase()).and_then(|s| self.position(s)))
+    }
+
+    /// The symbol with the given index, the index is taken by the modulus of the alphabet
+    pub fn symbol_at(&self, index: u32) -> char {
+        let shift = if self.one_based { self.modulus() - 1 } else { 0 };
+        self.symbols[((index % self.modulus() + shift) % self.modulus()) as usize]
+    }
+
+    /// Indices of all symbols of the message that belong to the alphabet, everything else is skipped
+    pub fn to_indices(&self, message: &str) -> Vec<u32> {
+        message
+            .chars()
+            .flat_map(|c| match self.index_of(c) {
+                Some(index) => vec![index],
+                // Letters like the Armenian "և" have multi-letter uppercase forms
+                None => c.to_uppercase().filter_map(|u| self.position(u)).collect(),
+            })
+            .collect()
+    }
+
+    /// Like `to_indices`, but fails on symbols which are not in the alphabet, whitespace is skipped
+    pub fn to_indices_strict(&self, message: &str) -> Result<Vec<u32>, CipherError> {
+        message
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| self.index_of(c).ok_or(CipherError::SymbolNotInAlphabet(c)))
+            .collect()
+    }
+
+    pub fn from_indices(&self, indices: &[u32]) -> String {
+        indices.iter().map(|i| self.symbol_at(*i)).collect()
+    }
+
+    /// Only the symbols of the message that belong to the alphabet, in their canonical case
+    pub fn normalize(&self, message: &str) -> String {
+        self.from_indices(&self.to_indices(message))
+    }
+
+    fn position(&self, symbol: char) -> Option<u32> {
+        let symbol = self
+            .equivalents
+            .iter()
+            .find(|(equivalent, _)| *equivalent == symbol)
+            .map_or(symbol, |(_, s)| *s);
+        let position = self.symbols.iter().position(|s| *s == symbol)? as u32;
+        Some(if self.one_based {
+            (position + 1) % self.modulus()
+        } else {
+            position
+        })
+    }
+
+    fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+#[cfg(test)]
+mod alphabet_test {
+    use crate::alphabet::Alphabet;
+    use crate::error::CipherError;
+
+    #[test]
+    fn english_is_one_based() {
+        let alphabet = Alphabet::english();
+        assert_eq!(alphabet.modulus(), 26);
+        assert_eq!(alphabet.index_of('A'), Some(1));
+        assert_eq!(alphabet.index_of('z'), Some(0));
+        assert_eq!(alphabet.symbol_at(1), 'A');
+        assert_eq!(alphabet.symbol_at(26), 'Z');
+        assert_eq!(alphabet.symbol_at(27), 'A');
+    }
+
+    #[test]
+    fn custom_alphabet_is_zero_based() {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap();
+        assert_eq!(alphabet.modulus(), 36);
+        assert_eq!(alphabet.index_of('a'), Some(0));
+        assert_eq!(alphabet.index_of('9'), Some(35));
+        assert_eq!(alphabet.index_of('?'), None);
+        assert_eq!(alphabet.symbol_at(35), '9');
+    }
+
+    #[test]
+    fn invalid_alphabets_are_rejected() {
+        assert_eq!(Alphabet::new(""), Err(CipherError::EmptyInput));
+        assert!(Alphabet::new("ABCA").is_err());
+        assert!(Alphabet::new("ABC").unwrap().with_frequencies(vec![0.5, 0.5]).is_err());
+        assert_eq!(
+            Alphabet::new("AB").unwrap().with_frequencies(vec![0.5, f64::NAN]),
+            Err(CipherError::InvalidAlphabet("NaN is not a frequency".to_owned()))
+        );
+        assert!(Alphabet::new("AB").unwrap().with_frequencies(vec![-0.5, 1.5]).is_err());
+    }
+
+    #[test]
+    fn normalize_skips_unknown_symbols() {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZÆØÅ").unwrap();
+        assert_eq!(alphabet.modulus(), 29);
+        assert_eq!(alphabet.normalize("Blåbær-syltetøy!"), "BLÅBÆRSYLTETØY".to_owned());
+    }
+
+    #[test]
+    fn to_indices_strict_fails_on_unknown_symbols() {
+        let alphabet = Alphabet::english();
+        assert_eq!(alphabet.to_indices_strict("ab c"), Ok(vec![1, 2, 3]));
+        assert_eq!(alphabet.to_indices_strict("ab-c"), Err(CipherError::SymbolNotInAlphabet('-')));
+    }
+
+    #[test]
+    fn indices_round_trip() {
+        let alphabet = Alphabet::english();
+        let indices = alphabet.to_indices("Drink water");
+        assert_eq!(alphabet.from_indices(&indices), "DRINKWATER".to_owned());
+    }
+}
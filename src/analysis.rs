@@ -0,0 +1,276 @@
+//! Frequency analysis of messages, the first step of breaking most classical ciphers.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::alphabet::Alphabet;
+use crate::scoring::frequencies::{index_of_coincidence, language_index_of_coincidence};
+
+/// The width of the longest bar of the histograms
+const HISTOGRAM_WIDTH: usize = 40;
+/// How many n-grams and repeated sequences are shown in the rendered report
+const RENDERED_ROWS: usize = 10;
+
+/// Letter, bigram and trigram statistics of a message. Only the symbols of the alphabet are counted,
+/// in their canonical case, and the n-grams are counted across the word boundaries, like the ciphers see them.
+///
+/// ```
+/// use cryptology_for_beginners::analysis::FrequencyReport;
+///
+/// let report = FrequencyReport::new("Hello there");
+/// assert_eq!(report.most_common_letters()[0], ('E', 3, 30.0));
+/// println!("{}", report);
+/// ```
+pub struct FrequencyReport {
+    letters: Vec<char>,
+    alphabet: Alphabet,
+}
+
+impl FrequencyReport {
+    pub fn new(message: &str) -> Self {
+        Self::with_alphabet(message, Alphabet::english())
+    }
+
+    /// The alphabet should have the letter frequencies of its language for the comparison
+    pub fn with_alphabet(message: &str, alphabet: Alphabet) -> Self {
+        FrequencyReport {
+            letters: alphabet.normalize(message).chars().collect(),
+            alphabet,
+        }
+    }
+
+    /// The number of the counted letters
+    pub fn total(&self) -> usize {
+        self.letters.len()
+    }
+
+    /// Every symbol of the alphabet with its count and percentage, in the order of the alphabet
+    pub fn letter_counts(&self) -> Vec<(char, usize, f64)> {
+        self.alphabet
+            .symbols()
+            .iter()
+            .map(|symbol| {
+                let count = self.letters.iter().filter(|c| *c == symbol).count();
+                (*symbol, count, Self::percentage(count, self.total()))
+            })
+            .collect()
+    }
+
+    /// The letters of the message with their counts and percentages, the most common first
+    pub fn most_common_letters(&self) -> Vec<(char, usize, f64)> {
+        let mut counts = self
+            .letter_counts()
+            .into_iter()
+            .filter(|x| x.1 > 0)
+            .collect::<Vec<(char, usize, f64)>>();
+        // The sort is stable, so the letters with the same count stay in the order of the alphabet
+        counts.sort_by_key(|x| std::cmp::Reverse(x.1));
+        counts
+    }
+
+    pub fn bigrams(&self) -> Vec<(String, usize, f64)> {
+        self.ngrams(2)
+    }
+
+    pub fn trigrams(&self) -> Vec<(String, usize, f64)> {
+        self.ngrams(3)
+    }
+
+    /// The n-grams of the message with their counts and percentages of all n-grams, the most common first
+    pub fn ngrams(&self, n: usize) -> Vec<(String, usize, f64)> {
+        if n == 0 || self.letters.len() < n {
+            return vec![];
+        }
+        let mut counts: HashMap<&[char], usize> = HashMap::new();
+        for ngram in self.letters.windows(n) {
+            *counts.entry(ngram).or_default() += 1;
+        }
+        let total = self.letters.len() - n + 1;
+        let mut ngrams = counts
+            .into_iter()
+            .map(|(ngram, count)| (ngram.iter().collect::<String>(), count, Self::percentage(count, total)))
+            .collect::<Vec<(String, usize, f64)>>();
+        ngrams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ngrams
+    }
+
+    /// None if the message has less than 2 letters
+    pub fn index_of_coincidence(&self) -> Option<f64> {
+        index_of_coincidence(&self.alphabet.to_indices(&self.letters.iter().collect::<String>()))
+    }
+
+    /// Shannon entropy of the letters in bits per letter, 0 for an empty message.
+    /// Substitutions keep it, while polyalphabetic ciphers make it closer to random text
+    pub fn entropy(&self) -> f64 {
+        self.letter_counts()
+            .iter()
+            .filter(|x| x.1 > 0)
+            .map(|x| {
+                let probability = x.1 as f64 / self.total() as f64;
+                -probability * probability.log2()
+            })
+            .sum()
+    }
+
+    /// The sequences of the given length found more than once with their positions (counted in letters),
+    /// the most repeated first. The distances between the positions help to find the key length of
+    /// polyalphabetic ciphers
+    pub fn repeated_sequences(&self, length: usize) -> Vec<(String, Vec<usize>)> {
+        if length == 0 || self.letters.len() < length {
+            return vec![];
+        }
+        let mut positions: HashMap<&[char], Vec<usize>> = HashMap::new();
+        for (i, sequence) in self.letters.windows(length).enumerate() {
+            positions.entry(sequence).or_default().push(i);
+        }
+        let mut sequences = positions
+            .into_iter()
+            .filter(|(_, positions)| positions.len() > 1)
+            .map(|(sequence, positions)| (sequence.iter().collect::<String>(), positions))
+            .collect::<Vec<(String, Vec<usize>)>>();
+        sequences.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.1[0].cmp(&b.1[0])));
+        sequences
+    }
+
+    /// Every symbol with its percentage in the message and in the language,
+    /// None if the alphabet has no frequencies
+    pub fn comparison(&self) -> Option<Vec<(char, f64, f64)>> {
+        let frequencies = self.alphabet.frequencies()?;
+        let total = frequencies.iter().sum::<f64>();
+        Some(
+            self.letter_counts()
+                .into_iter()
+                .zip(frequencies)
+                .map(|((symbol, _, percentage), frequency)| (symbol, percentage, frequency / total * 100.0))
+                .collect(),
+        )
+    }
+
+    /// The report as text with histograms of the letters, the same as the `Display` output
+    pub fn render(&self) -> String {
+        let mut report = format!("Letters: {}\n", self.total());
+        if let Some(index) = self.index_of_coincidence() {
+            report += &format!("Index of coincidence: {:.4}", index);
+            if let Some(expected) = language_index_of_coincidence(&self.alphabet) {
+                report += &format!(" (language: {:.4}, random: {:.4})", expected, 1.0 / self.alphabet.modulus() as f64);
+            }
+            report += "\n";
+        }
+        report += &format!("Entropy: {:.3} bits per letter\n", self.entropy());
+
+        report += "\nLetter frequencies\n";
+        let counts = self.letter_counts();
+        let highest = counts.iter().map(|x| x.2).fold(0.0, f64::max);
+        for (symbol, count, percentage) in &counts {
+            let line = format!("{} {:>5} {:>6.2}% {}", symbol, count, percentage, Self::bar(*percentage, highest));
+            report += line.trim_end();
+            report += "\n";
+        }
+
+        if let Some(comparison) = self.comparison() {
+            report += "\nComparison with the language\n";
+            report += "   message  language  difference\n";
+            for (symbol, observed, expected) in comparison {
+                report += &format!("{} {:>7.2}% {:>8.2}% {:>+10.2}%\n", symbol, observed, expected, observed - expected);
+            }
+        }
+
+        for (title, ngrams) in [("bigrams", self.bigrams()), ("trigrams", self.trigrams())] {
+            if ngrams.is_empty() {
+                continue;
+            }
+            report += &format!("\nMost common {}\n", title);
+            for (ngram, count, percentage) in ngrams.iter().take(RENDERED_ROWS) {
+                report += &format!("{} {:>5} {:>6.2}%\n", ngram, count, percentage);
+            }
+        }
+
+        let sequences = self.repeated_sequences(3);
+        if !sequences.is_empty() {
+            report += "\nRepeated sequences\n";
+            for (sequence, positions) in sequences.iter().take(RENDERED_ROWS) {
+                let positions = positions.iter().map(|p| p.to_string()).collect::<Vec<String>>();
+                report += &format!("{} at {}\n", sequence, positions.join(", "));
+            }
+        }
+        report
+    }
+
+    fn percentage(count: usize, total: usize) -> f64 {
+        if total == 0 {
+            return 0.0;
+        }
+        count as f64 / total as f64 * 100.0
+    }
+
+    fn bar(value: f64, highest: f64) -> String {
+        if highest <= 0.0 {
+            return String::new();
+        }
+        "#".repeat((value / highest * HISTOGRAM_WIDTH as f64).round() as usize)
+    }
+}
+
+impl fmt::Display for FrequencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+#[cfg(test)]
+mod analysis_test {
+    use crate::alphabet::Alphabet;
+    use crate::analysis::FrequencyReport;
+
+    #[test]
+    fn counts_work() {
+        let report = FrequencyReport::new("Hello there!");
+        assert_eq!(report.total(), 10);
+        assert_eq!(report.letter_counts()[4], ('E', 3, 30.0));
+        assert_eq!(
+            report.most_common_letters(),
+            vec![('E', 3, 30.0), ('H', 2, 20.0), ('L', 2, 20.0), ('O', 1, 10.0), ('R', 1, 10.0), ('T', 1, 10.0)]
+        );
+        assert_eq!(report.bigrams()[0], ("HE".to_owned(), 2, 2.0 / 9.0 * 100.0));
+        assert_eq!(report.trigrams().len(), 8);
+        assert!(report.ngrams(11).is_empty());
+    }
+
+    #[test]
+    fn statistics_work() {
+        let report = FrequencyReport::new("AABB");
+        assert_eq!(report.index_of_coincidence(), Some(1.0 / 3.0));
+        assert_eq!(report.entropy(), 1.0);
+        assert_eq!(FrequencyReport::new("AAAA").entropy(), 0.0);
+        assert_eq!(FrequencyReport::new("").index_of_coincidence(), None);
+    }
+
+    #[test]
+    fn repeated_sequences_work() {
+        let report = FrequencyReport::new("the cat and the hat and the bat");
+        assert_eq!(report.repeated_sequences(3)[0], ("THE".to_owned(), vec![0, 9, 18]));
+        assert!(report.repeated_sequences(5).contains(&("ANDTH".to_owned(), vec![6, 15])));
+    }
+
+    #[test]
+    fn comparison_works() {
+        let comparison = FrequencyReport::new("eeee").comparison().unwrap();
+        let (symbol, observed, expected) = comparison[4];
+        assert_eq!((symbol, observed), ('E', 100.0));
+        assert!(expected > 12.0 && expected < 13.0);
+        assert!(FrequencyReport::with_alphabet("AB", Alphabet::new("AB").unwrap())
+            .comparison()
+            .is_none());
+    }
+
+    #[test]
+    fn render_shows_histograms() {
+        let report = FrequencyReport::new("Hello there").render();
+        assert!(report.starts_with("Letters: 10\n"));
+        assert!(report.contains(&format!("E     3  30.00% {}\n", "#".repeat(40))));
+        assert!(report.contains(&format!("H     2  20.00% {}\n", "#".repeat(27))));
+        assert!(report.contains("A     0   0.00%\n"));
+        assert!(report.contains("E   30.00%    12.70%     +17.30%\n"));
+        assert!(report.contains("Most common bigrams\nHE     2  22.22%\n"));
+    }
+}
@@ -0,0 +1,168 @@
+//! Simulated annealing, the search shared by the hacks of the ciphers with too many keys to try them all:
+//! the key is changed a little at every step, a better key is always kept, a worse one sometimes,
+//! less and less often while the temperature goes down, so the search can get out of local maximums.
+
+use crate::random::Random;
+
+/// How the temperature goes down from the start to the end of a search
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Cooling {
+    /// Linearly from the temperature to 0
+    Linear(f64),
+    /// Exponentially from the first temperature to the second one
+    Exponential(f64, f64),
+}
+
+impl Cooling {
+    fn temperature(&self, progress: f64) -> f64 {
+        match *self {
+            Cooling::Linear(initial) => initial * (1.0 - progress),
+            Cooling::Exponential(initial, last) => initial * (last / initial).powf(progress),
+        }
+    }
+}
+
+/// The settings of the searches of a hack, changed with the builder methods of `annealing_builders!`
+pub(crate) struct Annealing {
+    pub(crate) seed: u64,
+    pub(crate) restarts: usize,
+    pub(crate) iterations: usize,
+}
+
+impl Annealing {
+    pub(crate) fn new(restarts: usize, iterations: usize) -> Self {
+        Annealing {
+            seed: 0,
+            restarts,
+            iterations,
+        }
+    }
+
+    /// One search from the key, gives the best key found with its fitness.
+    ///
+    /// The fitness is compared with the temperature, so the per-letter scores should be scaled
+    /// to the length of the message. `mutate` changes the key, if it returns false the key is the same
+    /// and the step is skipped
+    pub(crate) fn anneal<K: Clone>(
+        &self,
+        mut key: K,
+        cooling: Cooling,
+        random: &mut Random,
+        fitness: impl Fn(&K) -> f64,
+        mut mutate: impl FnMut(&mut K, &mut Random) -> bool,
+    ) -> (K, f64) {
+        let mut current = fitness(&key);
+        let (mut best_key, mut best) = (key.clone(), current);
+        for iteration in 0..self.iterations {
+            let temperature = cooling.temperature(iteration as f64 / self.iterations as f64);
+            let previous = key.clone();
+            if !mutate(&mut key, random) {
+                continue;
+            }
+            let new = fitness(&key);
+            let difference = new - current;
+            if difference >= 0.0 || (temperature > 0.0 && random.next_f64() < (difference / temperature).exp()) {
+                current = new;
+                if current > best {
+                    best = current;
+                    best_key = key.clone();
+                }
+            } else {
+                key = previous;
+            }
+        }
+        (best_key, best)
+    }
+}
+
+/// Tries all the swaps of two elements of the key while any of them makes the score better
+pub(crate) fn climb(mut key: Vec<usize>, mut score: f64, score_of: impl Fn(&[usize]) -> f64) -> (Vec<usize>, f64) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..key.len() {
+            for j in i + 1..key.len() {
+                key.swap(i, j);
+                let new_score = score_of(&key);
+                if new_score > score {
+                    score = new_score;
+                    improved = true;
+                } else {
+                    key.swap(i, j);
+                }
+            }
+        }
+    }
+    (key, score)
+}
+
+/// The builder methods of the hacks with the `scorer: Option<Box<dyn Scorer>>` and `annealing: Annealing`
+/// fields, with the default numbers of the restarts and of the iterations (the `RESTARTS` and `ITERATIONS`
+/// constants of the hack)
+macro_rules! annealing_builders {
+    ($restarts:literal, $iterations:literal) => {
+        const RESTARTS: usize = $restarts;
+        const ITERATIONS: usize = $iterations;
+
+        /// The scorer of the decrypted messages, quadgrams by default for English
+        pub fn with_scorer(mut self, scorer: impl $crate::scoring::Scorer + 'static) -> Self {
+            self.scorer = Some(Box::new(scorer));
+            self
+        }
+
+        /// The same seed gives the same result
+        pub fn with_seed(mut self, seed: u64) -> Self {
+            self.annealing.seed = seed;
+            self
+        }
+
+        #[doc = concat!("How many times the search is run from a new key, ", $restarts, " by default")]
+        pub fn with_restarts(mut self, restarts: usize) -> Self {
+            self.annealing.restarts = restarts.max(1);
+            self
+        }
+
+        #[doc = concat!("Steps of every search, ", $iterations, " by default")]
+        pub fn with_iterations(mut self, iterations: usize) -> Self {
+            self.annealing.iterations = iterations;
+            self
+        }
+    };
+}
+
+pub(crate) use annealing_builders;
+
+#[cfg(test)]
+mod annealing_test {
+    use crate::annealing::{climb, Annealing, Cooling};
+    use crate::random::Random;
+
+    /// Pairs of the elements in the wrong order, none for the sorted key
+    fn disorder(key: &[usize]) -> f64 {
+        let pairs = (0..key.len()).flat_map(|i| (i + 1..key.len()).map(move |j| (i, j)));
+        -(pairs.filter(|(i, j)| key[*i] > key[*j]).count() as f64)
+    }
+
+    #[test]
+    fn anneal_and_climb_sort_the_key() {
+        let mut random = Random::new(3);
+        let mut key = (0..10).collect::<Vec<usize>>();
+        random.shuffle(&mut key);
+        let annealing = Annealing::new(1, 2000);
+        let (key, score) = annealing.anneal(key, Cooling::Exponential(2.0, 0.1), &mut random, |k| disorder(k), |k, random| {
+            let (i, j) = (random.below(k.len()), random.below(k.len()));
+            k.swap(i, j);
+            i != j
+        });
+        assert_eq!(score, disorder(&key));
+        let (key, score) = climb(key, score, disorder);
+        assert_eq!(key, (0..10).collect::<Vec<usize>>());
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn cooling_ends_at_the_last_temperature() {
+        assert_eq!(Cooling::Linear(20.0).temperature(0.5), 10.0);
+        assert!((Cooling::Exponential(12.0, 4.0).temperature(1.0) - 4.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,17 @@
+//! All cryptanalysis helpers of the crate.
+
+pub use crate::fractionating::adfgvx::hacking::AdfgvxHack;
+pub use crate::monoalphabetic::additive::caesar::hacking::CaesarHack;
+pub use crate::monoalphabetic::affine::hacking::AffineCipherHack;
+pub use crate::monoalphabetic::multiplicative::hacking::MultiplicativeCipherHack;
+pub use crate::monoalphabetic::substitution::hacking::SubstitutionHack;
+pub use crate::polyalphabetic::four_square::hacking::FourSquareHack;
+pub use crate::polyalphabetic::hill::hacking::HillCipherHack;
+pub use crate::polyalphabetic::hill_digraph::hacking::HillDigraphCipherHack;
+pub use crate::polyalphabetic::playfair::hacking::PlayfairHack;
+pub use crate::polyalphabetic::two_square::hacking::TwoSquareHack;
+pub use crate::polyalphabetic::vigenere::hacking::VigenereHack;
+pub use crate::transposition::columnar::hacking::ColumnarHack;
+pub use crate::transposition::geometric::rail_fence::hacking::RailFenceHack;
+pub use crate::transposition::geometric::route::hacking::RouteHack;
+pub use crate::transposition::geometric::scytale::hacking::ScytaleHack;
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::error::CliError;
+
+/// Options without values
+const FLAGS: [&str; 5] = ["json", "segment", "brute-force", "padding", "help"];
+/// Options followed by a value
+const OPTIONS: [&str; 23] = [
+    "key",
+    "a",
+    "b",
+    "keyword",
+    "start",
+    "alphabet",
+    "format",
+    "input",
+    "output",
+    "depth",
+    "crib",
+    "size",
+    "top",
+    "seed",
+    "restarts",
+    "max-key-length",
+    "iterations",
+    "square",
+    "position",
+    "orientation",
+    "order",
+    "offset",
+    "route",
+];
+
+/// The parsed command line: `<command> [<cipher>] [--option value]... [--flag]...`
+#[derive(Debug)]
+pub struct Args {
+    pub command: String,
+    pub cipher: Option<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = vec![];
+        let mut options = HashMap::new();
+        let mut flags = HashSet::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = match arg.as_str() {
+                "-h" => "help",
+                "-i" => "input",
+                "-o" => "output",
+                arg => match arg.strip_prefix("--") {
+                    Some(name) => name,
+                    None => {
+                        positional.push(arg.to_lowercase());
+                        continue;
+                    }
+                },
+            };
+            if FLAGS.contains(&name) {
+                flags.insert(name.to_owned());
+            } else if OPTIONS.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+                options.insert(name.to_owned(), value.clone());
+            } else {
+                return Err(CliError::Usage(format!("unknown option {}", arg)));
+            }
+        }
+        let mut positional = positional.into_iter();
+        let command = positional.next().unwrap_or_else(|| "help".to_owned());
+        let cipher = positional.next();
+        if let Some(extra) = positional.next() {
+            return Err(CliError::Usage(format!("unexpected argument {}", extra)));
+        }
+        Ok(Args {
+            command,
+            cipher,
+            options,
+            flags,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|value| value.as_str())
+    }
+
+    pub fn required(&self, name: &str) -> Result<&str, CliError> {
+        self.get(name)
+            .ok_or_else(|| CliError::Usage(format!("--{} is required", name)))
+    }
+
+    /// The value of the option parsed as a number, None if it isn't given
+    pub fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>, CliError> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| CliError::Usage(format!("--{} should be a number, got {}", name, value)))
+            })
+            .transpose()
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+}
+
+#[cfg(test)]
+mod args_test {
+    use crate::args::Args;
+    use crate::error::CliError;
+
+    fn parse(args: &str) -> Result<Args, CliError> {
+        Args::parse(&args.split_whitespace().map(|x| x.to_owned()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn parse_works() {
+        let args = parse("crack Affine --depth 2 --json -i message.txt").unwrap();
+        assert_eq!((args.command.as_str(), args.cipher.as_deref()), ("crack", Some("affine")));
+        assert_eq!(args.number::<usize>("depth").unwrap(), Some(2));
+        assert_eq!(args.get("input"), Some("message.txt"));
+        assert!(args.flag("json") && !args.flag("segment"));
+        assert_eq!(parse("").unwrap().command, "help");
+    }
+
+    #[test]
+    fn invalid_arguments_are_reported() {
+        assert_eq!(parse("encrypt caesar --shift 3").err(), Some(CliError::Usage("unknown option --shift".to_owned())));
+        assert_eq!(parse("encrypt caesar --key").err(), Some(CliError::Usage("--key needs a value".to_owned())));
+        assert_eq!(
+            parse("encrypt caesar --key x").unwrap().number::<u8>("key").err(),
+            Some(CliError::Usage("--key should be a number, got x".to_owned()))
+        );
+        assert_eq!(parse("encrypt caesar 3").err(), Some(CliError::Usage("unexpected argument 3".to_owned())));
+    }
+}
@@ -0,0 +1,662 @@
+use cryptology_for_beginners::alphabet::Alphabet;
+use cryptology_for_beginners::analysis::FrequencyReport;
+use cryptology_for_beginners::attacks::{
+    AdfgvxHack, AffineCipherHack, CaesarHack, ColumnarHack, FourSquareHack, HillCipherHack, MultiplicativeCipherHack, PlayfairHack,
+    RailFenceHack, RouteHack, ScytaleHack, SubstitutionHack, TwoSquareHack, VigenereHack,
+};
+use cryptology_for_beginners::ciphers::{
+    Adfgvx, Affine, Caesar, Cipher, Columnar, DoubleColumnar, Format, FourSquare, Hill, Multiplicative, Myszkowski, Orientation,
+    Playfair, PolybiusSquare, RailFence, Route, RouteCipher, Scytale, Substitution, TwoSquare, Vigenere,
+};
+use cryptology_for_beginners::fractionating::adfgvx::manipulations::AdfgvxKey;
+use cryptology_for_beginners::modular::Matrix;
+use cryptology_for_beginners::polybius::{alphanumeric_alphabet, merged_alphabet};
+use cryptology_for_beginners::scoring::rank_or_default;
+use cryptology_for_beginners::segment::segment;
+
+use crate::args::Args;
+use crate::error::CliError;
+use crate::output::{Candidate, Output};
+
+/// How many candidates of an attack are shown by default
+const DEFAULT_TOP: usize = 5;
+
+/// The hack with the seed, the restarts and the iterations of its search given in the options
+macro_rules! searched {
+    ($hack:expr, $args:expr) => {{
+        let mut hack = $hack.with_seed($args.number("seed")?.unwrap_or(0));
+        if let Some(restarts) = $args.number("restarts")? {
+            hack = hack.with_restarts(restarts);
+        }
+        if let Some(iterations) = $args.number("iterations")? {
+            hack = hack.with_iterations(iterations);
+        }
+        hack
+    }};
+}
+
+pub fn run(args: &Args, input: String) -> Result<Output, CliError> {
+    match args.command.as_str() {
+        "encrypt" => transform(args, input, false),
+        "decrypt" => transform(args, input, true),
+        "crack" => crack(args, input),
+        "analyze" => Ok(Output::Report(FrequencyReport::with_alphabet(&input, alphabet(args)?))),
+        command => Err(CliError::Usage(format!("unknown command {}", command))),
+    }
+}
+
+fn transform(args: &Args, input: String, decrypt: bool) -> Result<Output, CliError> {
+    let alphabet = alphabet(args)?;
+    match cipher_name(args)? {
+        "caesar" => {
+            let key = required_number(args, "key")?;
+            apply(&Caesar::with_alphabet(key, alphabet), key.to_string(), args, input, decrypt)
+        }
+        "multiplicative" => {
+            let key = required_number(args, "key")?;
+            apply(&Multiplicative::with_alphabet(key, alphabet)?, key.to_string(), args, input, decrypt)
+        }
+        "affine" => {
+            let (a, b) = (required_number(args, "a")?, required_number(args, "b")?);
+            apply(&Affine::with_alphabet(a, b, alphabet)?, format!("{},{}", a, b), args, input, decrypt)
+        }
+        "hill" => {
+            let key = matrix(args.required("key")?)?;
+            let name = matrix_key(&key);
+            apply(&Hill::with_alphabet(key, alphabet)?, name, args, input, decrypt)
+        }
+        "vigenere" => {
+            let cipher = Vigenere::with_alphabet(args.required("key")?, alphabet)?;
+            apply(&cipher, cipher.key(), args, input, decrypt)
+        }
+        "substitution" => {
+            let cipher = match args.get("keyword") {
+                Some(keyword) => {
+                    let start = args.get("start").and_then(|s| s.chars().next());
+                    let start = start.unwrap_or(alphabet.symbols()[0]);
+                    Substitution::from_keyword_with_alphabet(keyword, start, alphabet)?
+                }
+                None => Substitution::with_alphabet(args.required("key")?, alphabet)?,
+            };
+            apply(&cipher, cipher.key(), args, input, decrypt)
+        }
+        "playfair" => {
+            let cipher = Playfair::with_square(square(args, "key", square_alphabet(args)?)?);
+            apply(&cipher, cipher.key(), args, input, decrypt)
+        }
+        "two-square" => {
+            let (first, second) = two_squares(args, square_alphabet(args)?)?;
+            let cipher = TwoSquare::with_squares(first, second)?.with_orientation(orientation(args)?);
+            apply(&cipher, squares_key(cipher.key()), args, input, decrypt)
+        }
+        "four-square" => {
+            let (first, second) = two_squares(args, square_alphabet(args)?)?;
+            let cipher = FourSquare::with_squares(first, second)?;
+            apply(&cipher, squares_key(cipher.key()), args, input, decrypt)
+        }
+        "columnar" => {
+            let cipher = columnar(args, args.get("order"), args.get("key"), alphabet)?;
+            apply(&cipher, order_key(&cipher.key()), args, input, decrypt)
+        }
+        "double-columnar" => {
+            let (first, second) = match args.get("order") {
+                Some(orders) => {
+                    let (first, second) = pair("order", orders)?;
+                    (columnar(args, Some(first), None, alphabet.clone())?, columnar(args, Some(second), None, alphabet)?)
+                }
+                None => {
+                    let (first, second) = pair("key", args.required("key")?)?;
+                    (columnar(args, None, Some(first), alphabet.clone())?, columnar(args, None, Some(second), alphabet)?)
+                }
+            };
+            let cipher = DoubleColumnar::with_ciphers(first, second)?;
+            let (first, second) = cipher.key();
+            apply(&cipher, format!("{}/{}", order_key(&first), order_key(&second)), args, input, decrypt)
+        }
+        "myszkowski" => {
+            let cipher = match args.get("order") {
+                Some(order_value) => Myszkowski::from_order_with_alphabet(&order(order_value)?, alphabet)?,
+                None => Myszkowski::with_alphabet(args.required("key")?, alphabet)?,
+            };
+            apply(&cipher, order_key(&cipher.key()), args, input, decrypt)
+        }
+        "rail-fence" => {
+            let cipher = RailFence::with_alphabet(required_number(args, "key")?, alphabet)?
+                .with_offset(args.number("offset")?.unwrap_or(0));
+            let (rails, offset) = cipher.key();
+            apply(&cipher, format!("{},{}", rails, offset), args, input, decrypt)
+        }
+        "scytale" => {
+            let cipher = Scytale::with_alphabet(required_number(args, "key")?, alphabet)?;
+            apply(&cipher, cipher.key().to_string(), args, input, decrypt)
+        }
+        "route" => {
+            let cipher = RouteCipher::with_alphabet(route(args.required("route")?)?, required_number(args, "key")?, alphabet)?;
+            let (route, columns) = cipher.key();
+            apply(&cipher, route_key(route, columns), args, input, decrypt)
+        }
+        "adfgvx" => {
+            let cipher = adfgvx(args, alphanumeric_alphabet())?;
+            apply(&cipher, adfgvx_key(cipher.key()), args, input, decrypt)
+        }
+        "adfgx" => {
+            let cipher = adfgvx(args, merged_alphabet('J', 'I')?)?;
+            apply(&cipher, adfgvx_key(cipher.key()), args, input, decrypt)
+        }
+        cipher => Err(unknown_cipher(cipher)),
+    }
+}
+
+fn apply<C: Cipher>(cipher: &C, key: String, args: &Args, input: String, decrypt: bool) -> Result<Output, CliError> {
+    let format = match args.get("format") {
+        Some("classical") => Format::Classical,
+        Some("preserve") => Format::Preserve,
+        Some("raw") => Format::Raw,
+        Some(format) => return Err(CliError::Usage(format!("unknown format {}", format))),
+        // The ciphertext is grouped, the plaintext isn't
+        None if decrypt => Format::Raw,
+        None => Format::Classical,
+    };
+    let text = if decrypt {
+        cipher.decrypt_with(input, format)?
+    } else {
+        cipher.encrypt_with(input, format)?
+    };
+    let text = if args.flag("segment") { segment(&text) } else { text };
+    Ok(Output::Message {
+        cipher: cipher.name(),
+        key,
+        text,
+    })
+}
+
+fn crack(args: &Args, input: String) -> Result<Output, CliError> {
+    let alphabet = alphabet(args)?;
+    let (cipher, candidates): (&'static str, Vec<(String, String, f64)>) = match cipher_name(args)? {
+        "caesar" => ("Caesar", keyed(CaesarHack::with_alphabet(input, alphabet).get_ranked_variants())),
+        "multiplicative" => (
+            "Multiplicative",
+            keyed(MultiplicativeCipherHack::with_alphabet(input, alphabet).get_ranked_variants()),
+        ),
+        "affine" => {
+            let hack = AffineCipherHack::with_alphabet(input, alphabet);
+            let options = if args.flag("brute-force") {
+                hack.brute_force()
+            } else {
+                let depth = args.number("depth")?.unwrap_or(1);
+                hack.rank_options(hack.get_all_options_based_on_common_letters_with_depth(depth))
+            };
+            let options = options.into_iter().map(|(a, b, text, score)| (format!("{},{}", a, b), text, score));
+            ("Affine", options.collect())
+        }
+        "hill" => {
+            let size = args.number("size")?.unwrap_or(2);
+            let keys = HillCipherHack::with_alphabet(input, size, alphabet.clone())?
+                .check_with_crib(args.required("crib")?.to_owned())?
+                .into_iter()
+                .map(|(key, text)| (matrix_key(&key), text))
+                .collect();
+            ("Hill", rank_or_default(keys, None, &alphabet))
+        }
+        "vigenere" => {
+            let mut hack = VigenereHack::with_alphabet(input, alphabet);
+            if let Some(length) = args.number("max-key-length")? {
+                hack = hack.with_max_key_length(length);
+            }
+            ("Vigenère", hack.get_ranked_variants())
+        }
+        "substitution" => {
+            let hack = searched!(SubstitutionHack::with_alphabet(input, alphabet), args);
+            ("Substitution", hack.get_ranked_variants())
+        }
+        "playfair" => {
+            let mut hack = searched!(PlayfairHack::with_alphabet(input, square_alphabet(args)?), args);
+            if let Some(crib) = args.get("crib") {
+                hack = hack.with_crib(crib, args.number("position")?.unwrap_or(0));
+            }
+            ("Playfair", hack.get_ranked_variants()?)
+        }
+        "two-square" => {
+            let mut hack = searched!(TwoSquareHack::with_alphabet(input, square_alphabet(args)?), args)
+                .with_orientation(orientation(args)?);
+            if let Some(crib) = args.get("crib") {
+                hack = hack.with_crib(crib, args.number("position")?.unwrap_or(0));
+            }
+            ("Two-square", squares_keyed(hack.get_ranked_variants()?))
+        }
+        "four-square" => {
+            let mut hack = searched!(FourSquareHack::with_alphabet(input, square_alphabet(args)?), args);
+            if let Some(crib) = args.get("crib") {
+                hack = hack.with_crib(crib, args.number("position")?.unwrap_or(0));
+            }
+            ("Four-square", squares_keyed(hack.get_ranked_variants()?))
+        }
+        "columnar" => {
+            let mut hack = searched!(ColumnarHack::with_alphabet(input, alphabet), args);
+            if let Some(length) = args.number("max-key-length")? {
+                hack = hack.with_max_key_length(length);
+            }
+            let variants = hack.get_ranked_variants().into_iter();
+            ("Columnar", variants.map(|(order, text, score)| (order_key(&order), text, score)).collect())
+        }
+        "rail-fence" => {
+            let mut hack = RailFenceHack::with_alphabet(input, alphabet);
+            if let Some(rails) = args.number("max-key-length")? {
+                hack = hack.with_max_rails(rails);
+            }
+            let variants = hack.get_ranked_variants().into_iter();
+            let variants = variants.map(|((rails, offset), text, score)| (format!("{},{}", rails, offset), text, score));
+            ("Rail fence", variants.collect())
+        }
+        "scytale" => ("Scytale", keyed(ScytaleHack::with_alphabet(input, alphabet).get_ranked_variants())),
+        "route" => {
+            let variants = RouteHack::with_alphabet(input, alphabet).get_ranked_variants().into_iter();
+            let variants = variants.map(|((route, columns), text, score)| (route_key(route, columns), text, score));
+            ("Route", variants.collect())
+        }
+        "adfgvx" => ("ADFGVX", adfgvx_keyed(adfgvx_hack(searched!(AdfgvxHack::new(input), args), args)?)),
+        "adfgx" => ("ADFGX", adfgvx_keyed(adfgvx_hack(searched!(AdfgvxHack::adfgx(input), args), args)?)),
+        cipher => return Err(unknown_cipher(cipher)),
+    };
+    let top = args.number("top")?.unwrap_or(DEFAULT_TOP);
+    let candidates = candidates
+        .into_iter()
+        .take(top)
+        .map(|(key, text, score)| Candidate {
+            key,
+            text: if args.flag("segment") { segment(&text) } else { text },
+            score,
+        })
+        .collect();
+    Ok(Output::Candidates { cipher, candidates })
+}
+
+fn keyed<K: ToString>(variants: Vec<(K, String, f64)>) -> Vec<(String, String, f64)> {
+    variants
+        .into_iter()
+        .map(|(key, text, score)| (key.to_string(), text, score))
+        .collect()
+}
+
+fn cipher_name(args: &Args) -> Result<&str, CliError> {
+    args.cipher
+        .as_deref()
+        .ok_or_else(|| CliError::Usage(format!("{} needs a cipher", args.command)))
+}
+
+fn unknown_cipher(cipher: &str) -> CliError {
+    CliError::Usage(format!("unknown cipher {}", cipher))
+}
+
+fn alphabet(args: &Args) -> Result<Alphabet, CliError> {
+    match args.get("alphabet").unwrap_or("english") {
+        "english" => Ok(Alphabet::english()),
+        "russian" => Ok(Alphabet::russian()),
+        "armenian" => Ok(Alphabet::armenian()),
+        "greek" => Ok(Alphabet::greek()),
+        "alphanumeric" => Ok(alphanumeric_alphabet()),
+        alphabet => Err(CliError::Usage(format!("unknown alphabet {}", alphabet))),
+    }
+}
+
+/// The alphabet of the Polybius squares, the English letters with J written as I by default
+fn square_alphabet(args: &Args) -> Result<Alphabet, CliError> {
+    match args.get("alphabet") {
+        Some(_) => alphabet(args),
+        None => Ok(merged_alphabet('J', 'I')?),
+    }
+}
+
+/// The square given by the keyword of the option, or by all of its symbols row by row with `--square`
+fn square(args: &Args, keyword: &str, alphabet: Alphabet) -> Result<PolybiusSquare, CliError> {
+    match args.get("square") {
+        Some(square) => square_of(square, true, alphabet),
+        None => square_of(args.required(keyword)?, false, alphabet),
+    }
+}
+
+/// The two squares of `--key` or `--square`, separated by a comma
+fn two_squares(args: &Args, alphabet: Alphabet) -> Result<(PolybiusSquare, PolybiusSquare), CliError> {
+    let (name, value, full) = match args.get("square") {
+        Some(square) => ("square", square, true),
+        None => ("key", args.required("key")?, false),
+    };
+    let (first, second) = value
+        .split_once(',')
+        .ok_or_else(|| CliError::Usage(format!("--{} should be two squares separated by a comma", name)))?;
+    Ok((square_of(first, full, alphabet.clone())?, square_of(second, full, alphabet)?))
+}
+
+/// The square of all the symbols row by row if `full`, otherwise of the keyword
+fn square_of(value: &str, full: bool, alphabet: Alphabet) -> Result<PolybiusSquare, CliError> {
+    if full {
+        Ok(PolybiusSquare::with_alphabet(value, alphabet)?)
+    } else {
+        Ok(PolybiusSquare::from_keyword_with_alphabet(value, alphabet)?)
+    }
+}
+
+/// The columnar transposition of the order (when every column is read) or of the keyword,
+/// with the padding of `--padding`
+fn columnar(args: &Args, order_value: Option<&str>, keyword: Option<&str>, alphabet: Alphabet) -> Result<Columnar, CliError> {
+    let cipher = match (order_value, keyword) {
+        (Some(order_value), _) => Columnar::from_order_with_alphabet(&order(order_value)?, alphabet)?,
+        (None, Some(keyword)) => Columnar::with_alphabet(keyword, alphabet)?,
+        (None, None) => return Err(CliError::Usage("--key is required".to_owned())),
+    };
+    Ok(cipher.with_padding(args.flag("padding")))
+}
+
+/// The two values of the option separated by `/` for the orders or by a comma for the keywords
+fn pair<'a>(name: &str, value: &'a str) -> Result<(&'a str, &'a str), CliError> {
+    let separator = if name == "order" { '/' } else { ',' };
+    value
+        .split_once(separator)
+        .ok_or_else(|| CliError::Usage(format!("--{} should be two values separated by {}", name, separator)))
+}
+
+/// The numbers separated by commas, like 6,3,2,4,1,5
+fn order(value: &str) -> Result<Vec<usize>, CliError> {
+    value
+        .split(',')
+        .map(|number| number.trim().parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| CliError::Usage(format!("the order should be numbers separated by commas, got {}", value)))
+}
+
+fn order_key(order: &[usize]) -> String {
+    order.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",")
+}
+
+/// The square of `--square` with the transposition of `--order`, or the square keyword and the
+/// transposition keyword of `--key`
+fn adfgvx(args: &Args, alphabet: Alphabet) -> Result<Adfgvx, CliError> {
+    let (square, order) = match args.get("square") {
+        Some(square) => (square_of(square, true, alphabet)?, order(args.required("order")?)?),
+        None => {
+            let (square, keyword) = pair("key", args.required("key")?)?;
+            (square_of(square, false, alphabet)?, Columnar::new(keyword)?.key())
+        }
+    };
+    Ok(Adfgvx::with_square(square, &order)?)
+}
+
+/// The variants found by the hack with the longest key of `--max-key-length`
+fn adfgvx_hack(hack: AdfgvxHack, args: &Args) -> Result<Vec<(AdfgvxKey, String, f64)>, CliError> {
+    let hack = match args.number("max-key-length")? {
+        Some(length) => hack.with_max_key_length(length),
+        None => hack,
+    };
+    Ok(hack.get_ranked_variants()?)
+}
+
+/// The square and the order separated by a space, like they are given to `--square` and `--order`
+fn adfgvx_key((square, order): AdfgvxKey) -> String {
+    format!("{} {}", square, order_key(&order))
+}
+
+fn adfgvx_keyed(variants: Vec<(AdfgvxKey, String, f64)>) -> Vec<(String, String, f64)> {
+    variants
+        .into_iter()
+        .map(|(key, text, score)| (adfgvx_key(key), text, score))
+        .collect()
+}
+
+fn route(name: &str) -> Result<Route, CliError> {
+    Route::all()
+        .iter()
+        .copied()
+        .find(|route| format!("{:?}", route).to_lowercase() == name)
+        .ok_or_else(|| CliError::Usage(format!("unknown route {}", name)))
+}
+
+/// The route and the number of the columns, like spiral,4
+fn route_key(route: Route, columns: usize) -> String {
+    format!("{},{}", format!("{:?}", route).to_lowercase(), columns)
+}
+
+fn orientation(args: &Args) -> Result<Orientation, CliError> {
+    match args.get("orientation") {
+        None | Some("vertical") => Ok(Orientation::Vertical),
+        Some("horizontal") => Ok(Orientation::Horizontal),
+        Some(orientation) => Err(CliError::Usage(format!("unknown orientation {}", orientation))),
+    }
+}
+
+/// The squares row by row, separated by a comma so they can be given to `--square`
+fn squares_key((first, second): (String, String)) -> String {
+    format!("{},{}", first, second)
+}
+
+fn squares_keyed(variants: Vec<((String, String), String, f64)>) -> Vec<(String, String, f64)> {
+    variants
+        .into_iter()
+        .map(|(squares, text, score)| (squares_key(squares), text, score))
+        .collect()
+}
+
+fn required_number<T: std::str::FromStr>(args: &Args, name: &str) -> Result<T, CliError> {
+    args.number(name)?
+        .ok_or_else(|| CliError::Usage(format!("--{} is required", name)))
+}
+
+/// The values of the matrix row by row, separated by commas or spaces
+fn matrix(values: &str) -> Result<Matrix, CliError> {
+    let values = values
+        .split([',', ' '])
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse::<i64>())
+        .collect::<Result<Vec<i64>, _>>()
+        .map_err(|_| CliError::Usage(format!("the key should be numbers, got {}", values)))?;
+    Ok(Matrix::new(values)?)
+}
+
+fn matrix_key(matrix: &Matrix) -> String {
+    matrix
+        .values()
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod commands_test {
+    use cryptology_for_beginners::ciphers::{Cipher, Columnar, Playfair, PolybiusSquare, TwoSquare};
+    use cryptology_for_beginners::error::CipherError;
+    use cryptology_for_beginners::polybius::merged_alphabet;
+
+    use crate::args::Args;
+    use crate::commands::run;
+    use crate::error::CliError;
+    use crate::output::Output;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us";
+    const LONG_MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way";
+
+    fn run_text(args: &str, input: &str) -> Result<String, CliError> {
+        let args = Args::parse(&args.split_whitespace().map(|x| x.to_owned()).collect::<Vec<String>>())?;
+        run(&args, input.to_owned()).map(|output| output.to_text())
+    }
+
+    #[test]
+    fn encrypt_and_decrypt_work() {
+        assert_eq!(run_text("encrypt caesar --key 3", "Hello world"), Ok("KHOOR ZRUOG".to_owned()));
+        assert_eq!(run_text("decrypt caesar --key 3", "KHOOR ZRUOG"), Ok("HELLOWORLD".to_owned()));
+        assert_eq!(
+            run_text("encrypt affine --a 7 --b 15 --format preserve", "Don't forget!"),
+            Ok("Qpi'y epklxy!".to_owned())
+        );
+        assert_eq!(run_text("decrypt affine --a 7 --b 15 --segment", "QPIYE PKLXY"), Ok("DONT FORGET".to_owned()));
+        assert_eq!(run_text("encrypt hill --key 5,3,9,6", "meet"), Ok("BQGI".to_owned()));
+        assert_eq!(run_text("encrypt vigenere --key lemon", "attack at dawn"), Ok("LXFOP VEFRN HR".to_owned()));
+        assert_eq!(
+            run_text("encrypt substitution --keyword zebras", "flee at once"),
+            Ok("SIAAZ QLKBA".to_owned())
+        );
+        assert_eq!(run_text("encrypt multiplicative --key 7 --format raw", "ab c"), Ok("GNU".to_owned()));
+        assert_eq!(
+            run_text("encrypt vigenere --key lemon --format preserve", "attack at dawn"),
+            Err(CliError::Cipher(CipherError::UnsupportedFormat("Vigenère")))
+        );
+    }
+
+    #[test]
+    fn crack_works() {
+        let output = run_text("crack caesar --top 1", "QUPCV OZGTM BAOMB IXQHH I").unwrap();
+        assert!(output.starts_with("8: IMHUNGRYLETSGETAPIZZA ("), "{}", output);
+        let output = run_text("crack affine --depth 2 --top 1 --segment", "QPIYE PKLXY YPRXX YSXXU XWSVI YR").unwrap();
+        assert!(output.starts_with("7,15: DONT FORGET TO SEE THE ELEPHANTS ("), "{}", output);
+        let output = run_text(
+            "crack hill --crib STEVE --top 1",
+            "KMYEM UPAUO AHOJR YUKTT CACQC XXIYE DKSTQ ZXDAW",
+        )
+        .unwrap();
+        assert!(output.starts_with("5,3,9,6: IFSTEVEWANTSTOKEEPTHEJOBHEMUSTWORKHARDER ("), "{}", output);
+    }
+
+    #[test]
+    fn playfair_works() {
+        assert_eq!(run_text("encrypt playfair --key monarchy", "instruments"), Ok("GATLM ZCLRQ XA".to_owned()));
+        assert_eq!(run_text("decrypt playfair --key monarchy", "GATLM ZCLRQ XA"), Ok("INSTRUMENTSX".to_owned()));
+        let square = "MONARCHYBDEFGIKLPQSTUVWXZ";
+        assert_eq!(
+            run_text(&format!("encrypt playfair --square {}", square), "instruments"),
+            Ok("GATLM ZCLRQ XA".to_owned())
+        );
+        let square = PolybiusSquare::random(4, merged_alphabet('J', 'I').unwrap()).unwrap();
+        let encrypted = Playfair::with_square(square).encrypt(MESSAGE.to_owned());
+        let output = run_text(
+            "crack playfair --crib ITWASTHEBESTOF --seed 10 --restarts 3 --iterations 30000 --top 1",
+            &encrypted,
+        )
+        .unwrap();
+        assert!(output.contains(": ITWASTHEBESTOFTIMESITWASTHEWORSTOFTIMES"), "{}", output);
+    }
+
+    #[test]
+    fn two_square_and_four_square_work() {
+        let encrypted = run_text("encrypt two-square --key example,keyword", "help me obi wan kenobi").unwrap();
+        assert_eq!(run_text("decrypt two-square --key example,keyword", &encrypted), Ok("HELPMEOBIWANKENOBI".to_owned()));
+        let horizontal = run_text("encrypt two-square --key example,keyword --orientation horizontal", "help me").unwrap();
+        assert_ne!(horizontal, run_text("encrypt two-square --key example,keyword", "help me").unwrap());
+        let encrypted = run_text("encrypt four-square --key example,keyword", "help me obi wan kenobi").unwrap();
+        assert_ne!(encrypted, run_text("encrypt two-square --key example,keyword", "help me obi wan kenobi").unwrap());
+        assert_eq!(run_text("decrypt four-square --key example,keyword", &encrypted), Ok("HELPMEOBIWANKENOBI".to_owned()));
+        // A short search is enough to check that the found squares decrypt to the found message
+        let encrypted = TwoSquare::new("example", "keyword").unwrap().encrypt(LONG_MESSAGE.to_owned());
+        for cipher in ["two-square", "four-square"] {
+            let output = run_text(&format!("crack {} --crib ITWAS --restarts 1 --iterations 100", cipher), &encrypted).unwrap();
+            for line in output.lines() {
+                let (squares, rest) = line.split_once(": ").unwrap();
+                let (message, _) = rest.split_once(" (").unwrap();
+                let decrypted = run_text(&format!("decrypt {} --square {}", cipher, squares), &encrypted).unwrap();
+                assert_eq!(decrypted, message);
+            }
+        }
+        assert_eq!(
+            run_text("encrypt two-square --key example", ""),
+            Err(CliError::Usage("--key should be two squares separated by a comma".to_owned()))
+        );
+    }
+
+    #[test]
+    fn columnar_transpositions_work() {
+        let message = "We are discovered, flee at once";
+        assert_eq!(run_text("encrypt columnar --key zebras", message), Ok("EVLNA CDTES EAROF ODEEC WIREE".to_owned()));
+        assert_eq!(
+            run_text("decrypt columnar --order 6,3,2,4,1,5", "EVLNA CDTES EAROF ODEEC WIREE"),
+            Ok("WEAREDISCOVEREDFLEEATONCE".to_owned())
+        );
+        assert_eq!(run_text("encrypt columnar --key zebras --padding --format raw", "abcdefg").map(|t| t.len()), Ok(12));
+        let encrypted = run_text("encrypt double-columnar --key zebras,striped", message).unwrap();
+        assert_eq!(
+            run_text("decrypt double-columnar --order 6,3,2,4,1,5/6,7,5,3,4,2,1", &encrypted),
+            Ok("WEAREDISCOVEREDFLEEATONCE".to_owned())
+        );
+        let encrypted = run_text("encrypt myszkowski --key tomato", message).unwrap();
+        assert_eq!(run_text("decrypt myszkowski --order 4,3,2,1,4,3", &encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+        let encrypted = Columnar::new("zebras").unwrap().encrypt(MESSAGE.to_owned());
+        let output = run_text("crack columnar --max-key-length 6 --top 1", &encrypted).unwrap();
+        assert!(output.starts_with("6,3,2,4,1,5: ITWASTHEBESTOFTIMES"), "{}", output);
+        assert_eq!(
+            run_text("decrypt columnar --order 6,3,x", ""),
+            Err(CliError::Usage("the order should be numbers separated by commas, got 6,3,x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn geometric_transpositions_work() {
+        let message = "We are discovered, flee at once";
+        assert_eq!(run_text("encrypt rail-fence --key 3", message), Ok("WECRL TEERD SOEEF EAOCA IVDEN".to_owned()));
+        let encrypted = run_text("encrypt rail-fence --key 3 --offset 2", message).unwrap();
+        assert_eq!(run_text("decrypt rail-fence --key 3 --offset 2", &encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+        let encrypted = run_text("encrypt scytale --key 4", message).unwrap();
+        assert_eq!(run_text("decrypt scytale --key 4", &encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+        for route in ["spiral", "snake", "diagonal"] {
+            let encrypted = run_text(&format!("encrypt route --route {} --key 5", route), message).unwrap();
+            let decrypted = run_text(&format!("decrypt route --route {} --key 5", route), &encrypted);
+            assert_eq!(decrypted, Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+        }
+        assert_eq!(run_text("encrypt route --route zigzag --key 5", ""), Err(CliError::Usage("unknown route zigzag".to_owned())));
+        let encrypted = run_text("encrypt rail-fence --key 4 --offset 1", MESSAGE).unwrap();
+        let output = run_text("crack rail-fence --max-key-length 6 --top 1", &encrypted).unwrap();
+        assert!(output.starts_with("4,1: ITWASTHEBESTOFTIMES"), "{}", output);
+        let encrypted = run_text("encrypt scytale --key 7", MESSAGE).unwrap();
+        assert!(run_text("crack scytale --top 1", &encrypted).unwrap().starts_with("7: ITWASTHEBEST"));
+        let encrypted = run_text("encrypt route --route snake --key 6", MESSAGE).unwrap();
+        assert!(run_text("crack route --top 1", &encrypted).unwrap().starts_with("snake,6: ITWASTHEBEST"));
+    }
+
+    #[test]
+    fn adfgvx_and_adfgx_work() {
+        assert_eq!(
+            run_text("encrypt adfgx --key btalpdhozkqfvsngicuxmrewy,cargo", "Attack at once"),
+            Ok("FAXDF ADDDG DGFFF AFAXA FAFX".to_owned())
+        );
+        let encrypted = run_text("encrypt adfgvx --key key,word", "Meet me at 10:45").unwrap();
+        assert_eq!(run_text("decrypt adfgvx --key key,word", &encrypted), Ok("MEETMEAT1045".to_owned()));
+        let square = "KEYABCDFGHIJLMNOPQRSTUVWXZ0123456789";
+        let decrypted = run_text(&format!("decrypt adfgvx --square {} --order 4,2,3,1", square), &encrypted);
+        assert_eq!(decrypted, Ok("MEETMEAT1045".to_owned()));
+        // A short search is enough to check that the found key decrypts to the found message
+        let encrypted = run_text("encrypt adfgx --key key,word", MESSAGE).unwrap();
+        let output = run_text("crack adfgx --max-key-length 4 --restarts 1 --iterations 100", &encrypted).unwrap();
+        let (square, rest) = output.split_once(' ').unwrap();
+        let (order, rest) = rest.split_once(": ").unwrap();
+        let (message, _) = rest.split_once(" (").unwrap();
+        let decrypted = run_text(&format!("decrypt adfgx --square {} --order {}", square, order), &encrypted);
+        assert_eq!(decrypted, Ok(message.to_owned()));
+    }
+
+    #[test]
+    fn analyze_works() {
+        let args = Args::parse(&["analyze".to_owned()]).unwrap();
+        match run(&args, "Hello there".to_owned()).unwrap() {
+            Output::Report(report) => assert_eq!(report.total(), 10),
+            _ => panic!("expected a report"),
+        }
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(run_text("encrypt rot47 --key 3", ""), Err(CliError::Usage("unknown cipher rot47".to_owned())));
+        assert_eq!(run_text("encrypt caesar", ""), Err(CliError::Usage("--key is required".to_owned())));
+        assert_eq!(run_text("crack", ""), Err(CliError::Usage("crack needs a cipher".to_owned())));
+        assert_eq!(
+            run_text("encrypt affine --a 4 --b 3", ""),
+            Err(CliError::Cipher(CipherError::NonInvertibleKey { value: 4, modulus: 26 }))
+        );
+        assert_eq!(
+            run_text("encrypt caesar --key 3 --format fancy", ""),
+            Err(CliError::Usage("unknown format fancy".to_owned()))
+        );
+    }
+}
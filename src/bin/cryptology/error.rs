@@ -0,0 +1,38 @@
+use std::fmt;
+
+use cryptology_for_beginners::error::CipherError;
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    /// Wrong arguments, the usage is printed with the message
+    Usage(String),
+    Cipher(CipherError),
+    /// Reading the input or writing the output failed
+    Io(String),
+}
+
+impl CliError {
+    /// The exit code of the binary
+    pub fn code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Cipher(error) => write!(f, "{}", error),
+            CliError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<CipherError> for CliError {
+    fn from(error: CipherError) -> Self {
+        CliError::Cipher(error)
+    }
+}
@@ -0,0 +1,106 @@
+//! Command-line interface of the ciphers and the attacks of the crate.
+
+mod args;
+mod commands;
+mod error;
+mod output;
+
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+use crate::args::Args;
+use crate::error::CliError;
+
+const USAGE: &str = "\
+Usage: cryptology <command> [<cipher>] [options]
+
+Commands:
+  encrypt <cipher>   encrypt the input with the key
+  decrypt <cipher>   decrypt the input with the key
+  crack <cipher>     find the key of the input, the best candidates first
+  analyze            letter frequencies of the input
+
+Ciphers and their keys:
+  caesar             --key <shift>
+  multiplicative     --key <factor>
+  affine             --a <factor> --b <shift>
+  hill               --key <matrix values row by row, like 5,3,9,6>
+  vigenere           --key <keyword>
+  substitution       --key <ciphertext alphabet> or --keyword <keyword> [--start <letter>]
+  playfair           --key <keyword> or --square <the square row by row>
+  two-square         --key <keyword>,<keyword> or --square <square>,<square> [--orientation horizontal]
+  four-square        --key <keyword>,<keyword> or --square <square>,<square> (the upper right and lower left)
+  columnar           --key <keyword> or --order <when every column is read, like 6,3,2,4,1,5> [--padding]
+  double-columnar    --key <keyword>,<keyword> or --order <order>/<order> [--padding]
+  myszkowski         --key <keyword> or --order <order, the columns read together have the same number>
+  rail-fence         --key <rails> [--offset <n>]
+  scytale            --key <letters around the rod>
+  route              --route <spiral, snake or diagonal> --key <columns>
+  adfgvx, adfgx      --key <square keyword>,<keyword> or --square <square> --order <order>
+
+Attack options:
+  --top <n>              how many candidates are shown, 5 by default
+  --depth <n>            affine: how many of the most common letters are tried
+  --brute-force          affine: try every key
+  --crib <text>          hill, playfair, two-square, four-square: known part of the plaintext
+  --position <n>         playfair, two-square, four-square: the letter of the plaintext where the crib
+                         starts, 0 by default
+  --size <n>             hill: the size of the key matrix, 2 by default
+  --max-key-length <n>   vigenere, columnar, rail-fence, adfgvx, adfgx: the longest key (or the most rails)
+                         that is checked
+  --seed <n>, --restarts <n>, --iterations <n>
+                         substitution, playfair, two-square, four-square, columnar, adfgvx, adfgx:
+                         the settings of the search
+
+Other options:
+  -i, --input <file>     read the input from the file instead of stdin
+  -o, --output <file>    write the output to the file instead of stdout
+  --format <format>      classical (groups of 5), preserve (keep case and punctuation, only for caesar,
+                         multiplicative, affine and hill) or raw
+  --alphabet <name>      english (default), russian, armenian, greek or alphanumeric (the 6×6 squares),
+                         the squares are the English letters with J written as I by default
+  --segment              split the decrypted messages into words
+  --json                 print the output as JSON
+  -h, --help             print this help
+";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Err(error) = run(&args) {
+        eprintln!("cryptology: {}", error);
+        if let CliError::Usage(_) = error {
+            eprintln!("\n{}", USAGE);
+        }
+        process::exit(error.code());
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    if args.flag("help") || args.command == "help" {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    let input = match args.get("input") {
+        Some(path) => fs::read_to_string(path).map_err(|error| CliError::Io(format!("can't read {}: {}", path, error)))?,
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|error| CliError::Io(format!("can't read the input: {}", error)))?;
+            input
+        }
+    };
+    // The newline at the end of the input isn't a part of the message
+    let input = input.trim_end_matches(['\n', '\r']).to_owned();
+    let output = commands::run(&args, input)?;
+    let output = if args.flag("json") { output.to_json() } else { output.to_text() };
+    match args.get("output") {
+        Some(path) => fs::write(path, output + "\n").map_err(|error| CliError::Io(format!("can't write {}: {}", path, error))),
+        None => {
+            println!("{}", output);
+            Ok(())
+        }
+    }
+}
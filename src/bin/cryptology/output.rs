@@ -0,0 +1,134 @@
+use cryptology_for_beginners::analysis::FrequencyReport;
+
+/// The result of a command, printed as plain text or JSON
+pub enum Output {
+    /// The encrypted or decrypted message
+    Message { cipher: &'static str, key: String, text: String },
+    /// The keys found by an attack, the best one first
+    Candidates { cipher: &'static str, candidates: Vec<Candidate> },
+    Report(FrequencyReport),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+    pub key: String,
+    pub text: String,
+    pub score: f64,
+}
+
+impl Output {
+    pub fn to_text(&self) -> String {
+        match self {
+            Output::Message { text, .. } => text.clone(),
+            Output::Candidates { candidates, .. } => candidates
+                .iter()
+                .map(|c| format!("{}: {} ({:.3})", c.key, c.text, c.score))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Output::Report(report) => report.render().trim_end().to_owned(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Output::Message { cipher, key, text } => format!(
+                "{{\"cipher\":{},\"key\":{},\"text\":{}}}",
+                string(cipher),
+                string(key),
+                string(text)
+            ),
+            Output::Candidates { cipher, candidates } => {
+                let candidates = candidates
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "{{\"key\":{},\"text\":{},\"score\":{}}}",
+                            string(&c.key),
+                            string(&c.text),
+                            number(c.score)
+                        )
+                    })
+                    .collect::<Vec<String>>();
+                format!("{{\"cipher\":{},\"candidates\":[{}]}}", string(cipher), candidates.join(","))
+            }
+            Output::Report(report) => {
+                let letters = report
+                    .letter_counts()
+                    .iter()
+                    .map(|(letter, count, percentage)| {
+                        format!(
+                            "{{\"letter\":{},\"count\":{},\"percentage\":{}}}",
+                            string(&letter.to_string()),
+                            count,
+                            number(*percentage)
+                        )
+                    })
+                    .collect::<Vec<String>>();
+                format!(
+                    "{{\"letters\":{},\"index_of_coincidence\":{},\"entropy\":{},\"frequencies\":[{}]}}",
+                    report.total(),
+                    report.index_of_coincidence().map_or("null".to_owned(), number),
+                    number(report.entropy()),
+                    letters.join(",")
+                )
+            }
+        }
+    }
+}
+
+/// JSON string with the quotes
+fn string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\r' => result += "\\r",
+            '\t' => result += "\\t",
+            c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// JSON has no infinities and NaN
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod output_test {
+    use crate::output::{number, string, Candidate, Output};
+
+    #[test]
+    fn json_values_are_escaped() {
+        assert_eq!(string("say \"hi\"\n\\"), "\"say \\\"hi\\\"\\n\\\\\"");
+        assert_eq!(string("\u{1}ա"), "\"\\u0001ա\"");
+        assert_eq!(number(-1.5), "-1.5");
+        assert_eq!(number(f64::NEG_INFINITY), "null");
+    }
+
+    #[test]
+    fn candidates_are_rendered() {
+        let output = Output::Candidates {
+            cipher: "Caesar",
+            candidates: vec![Candidate {
+                key: "3".to_owned(),
+                text: "HELLO".to_owned(),
+                score: -2.25,
+            }],
+        };
+        assert_eq!(output.to_text(), "3: HELLO (-2.250)");
+        assert_eq!(
+            output.to_json(),
+            "{\"cipher\":\"Caesar\",\"candidates\":[{\"key\":\"3\",\"text\":\"HELLO\",\"score\":-2.25}]}"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+use crate::format::Format;
+
+/// Common interface of every cipher in the crate, so ciphers can be used generically
+/// (swapped in pipelines, tested with the same round-trip checks and so on).
+pub trait Cipher {
+    /// The type of the key the cipher was created with
+    type Key;
+
+    /// Human readable name of the cipher
+    fn name(&self) -> &'static str;
+
+    fn key(&self) -> Self::Key;
+
+    /// The alphabet the cipher works with
+    fn alphabet(&self) -> &Alphabet;
+
+    fn encrypt(&self, message: String) -> String;
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError>;
+
+    /// Whether every letter of the output stands in the place of a letter of the input, so `Format::Preserve`
+    /// can lay the output out like the input. Ciphers that move the letters or add some (besides the padding
+    /// at the end) don't
+    fn preserves_positions(&self) -> bool {
+        false
+    }
+
+    /// Encrypts with the output in the given format, `Format::Preserve` keeps the case,
+    /// spaces and punctuation of the message and is only supported if `preserves_positions` is true
+    fn encrypt_with(&self, message: String, format: Format) -> Result<String, CipherError> {
+        check_format(self, format)?;
+        let encrypted = self.encrypt(message.clone());
+        Ok(format.apply(&message, &encrypted, self.alphabet()))
+    }
+
+    /// Decrypts with the output in the given format, `Format::Preserve` keeps the layout of the encrypted message
+    /// and is only supported if `preserves_positions` is true
+    fn decrypt_with(&self, encrypted_message: String, format: Format) -> Result<String, CipherError> {
+        check_format(self, format)?;
+        let decrypted = self.decrypt(encrypted_message.clone())?;
+        Ok(format.apply(&encrypted_message, &decrypted, self.alphabet()))
+    }
+}
+
+fn check_format<C: Cipher + ?Sized>(cipher: &C, format: Format) -> Result<(), CipherError> {
+    if format == Format::Preserve && !cipher.preserves_positions() {
+        return Err(CipherError::UnsupportedFormat(cipher.name()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod cipher_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::format::Format;
+    use crate::fractionating::adfgvx::manipulations::AdfgvxCipher;
+    use crate::monoalphabetic::additive::caesar::manipulations::CaesarCipher;
+    use crate::monoalphabetic::affine::manipulations::AffineCipher;
+    use crate::monoalphabetic::multiplicative::manipulations::MultiplicativeCipher;
+    use crate::monoalphabetic::substitution::manipulations::SubstitutionCipher;
+    use crate::polyalphabetic::four_square::manipulations::FourSquareCipher;
+    use crate::polyalphabetic::hill_digraph::manipulations::HillDigraphCipher;
+    use crate::polyalphabetic::playfair::manipulations::PlayfairCipher;
+    use crate::polyalphabetic::two_square::manipulations::TwoSquareCipher;
+    use crate::polyalphabetic::vigenere::manipulations::VigenereCipher;
+    use crate::transposition::columnar::manipulations::ColumnarCipher;
+    use crate::transposition::double_columnar::manipulations::DoubleColumnarCipher;
+    use crate::transposition::geometric::rail_fence::manipulations::RailFenceCipher;
+    use crate::transposition::geometric::route::manipulations::{Route, RouteCipher};
+    use crate::transposition::geometric::scytale::manipulations::ScytaleCipher;
+    use crate::transposition::myszkowski::manipulations::MyszkowskiCipher;
+
+    fn assert_round_trip<C: Cipher>(cipher: &C, message: &str, expected: &str) {
+        let encrypted = cipher.encrypt(message.to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok(expected.to_owned()), "{}", cipher.name());
+    }
+
+    #[test]
+    fn round_trip_works_for_all_ciphers() {
+        let message = "Meet me at the mall";
+        let expected = "MEETMEATTHEMALL";
+        assert_round_trip(&CaesarCipher::new(3), message, expected);
+        assert_round_trip(&MultiplicativeCipher::new(7).unwrap(), message, expected);
+        assert_round_trip(&AffineCipher::new(11, 6).unwrap(), message, expected);
+        assert_round_trip(&SubstitutionCipher::from_keyword("zebras", 'a').unwrap(), message, expected);
+        assert_round_trip(&HillDigraphCipher::new([5, 3, 9, 6]).unwrap(), message, "MEETMEATTHEMALLX");
+        assert_round_trip(&VigenereCipher::new("lemon").unwrap(), message, expected);
+        assert_round_trip(&PlayfairCipher::new("monarchy").unwrap(), message, "MEETMEATTHEMALLX");
+        assert_round_trip(&TwoSquareCipher::new("example", "keyword").unwrap(), message, "MEETMEATTHEMALLX");
+        assert_round_trip(&FourSquareCipher::new("example", "keyword").unwrap(), message, "MEETMEATTHEMALLX");
+        assert_round_trip(&ColumnarCipher::new("zebras").unwrap(), message, expected);
+        assert_round_trip(&DoubleColumnarCipher::new("zebras", "striped").unwrap(), message, expected);
+        assert_round_trip(&MyszkowskiCipher::new("tomato").unwrap(), message, expected);
+        assert_round_trip(&RailFenceCipher::new(3).unwrap().with_offset(2), message, expected);
+        assert_round_trip(&ScytaleCipher::new(4).unwrap(), message, expected);
+        for route in Route::all() {
+            assert_round_trip(&RouteCipher::new(route, 4).unwrap(), message, expected);
+        }
+        assert_round_trip(&AdfgvxCipher::new("key", "word").unwrap(), message, expected);
+        assert_round_trip(&AdfgvxCipher::adfgx("key", "word").unwrap(), message, expected);
+    }
+
+    #[test]
+    fn formats_work_for_all_ciphers() {
+        let message = "Meet me at the mall, at 9!";
+        let multiplicative = MultiplicativeCipher::new(7).unwrap();
+        assert_eq!(
+            multiplicative.encrypt_with(message.to_owned(), Format::Classical),
+            Ok(multiplicative.encrypt(message.to_owned()))
+        );
+        assert_eq!(multiplicative.encrypt_with(message.to_owned(), Format::Raw), Ok("MIIJMIGJJDIMGFFGJ".to_owned()));
+        let caesar = CaesarCipher::new(3);
+        let encrypted = caesar.encrypt_with(message.to_owned(), Format::Preserve).unwrap();
+        assert_eq!(encrypted, "Phhw ph dw wkh pdoo, dw 9!");
+        assert_eq!(caesar.decrypt_with(encrypted, Format::Preserve), Ok(message.to_owned()));
+        let affine = AffineCipher::new(11, 6).unwrap();
+        let encrypted = affine.encrypt_with(message.to_owned(), Format::Preserve).unwrap();
+        assert_eq!(affine.decrypt_with(encrypted, Format::Preserve), Ok(message.to_owned()));
+        // The padding of the last block goes to the end, after the punctuation
+        let hill = HillDigraphCipher::new([5, 3, 9, 6]).unwrap();
+        let encrypted = hill.encrypt_with("Hi, Bob!".to_owned(), Format::Preserve).unwrap();
+        assert_eq!(encrypted, "Ov, Cdd!F");
+        assert_eq!(hill.decrypt_with(encrypted, Format::Preserve), Ok("Hi, Bob!X".to_owned()));
+        assert_eq!(caesar.decrypt_with("KHOOR ZRUOG".to_owned(), Format::Classical), Ok("HELLO WORLD".to_owned()));
+    }
+
+    #[test]
+    fn preserve_is_rejected_by_ciphers_moving_letters() {
+        let message = "Meet me at the mall";
+        let playfair = PlayfairCipher::new("monarchy").unwrap();
+        assert_eq!(
+            playfair.encrypt_with(message.to_owned(), Format::Preserve),
+            Err(CipherError::UnsupportedFormat("Playfair"))
+        );
+        let columnar = ColumnarCipher::new("zebras").unwrap();
+        let encrypted = columnar.encrypt_with(message.to_owned(), Format::Raw).unwrap();
+        assert!(matches!(
+            columnar.decrypt_with(encrypted.clone(), Format::Preserve),
+            Err(CipherError::UnsupportedFormat(_))
+        ));
+        assert_eq!(columnar.decrypt_with(encrypted, Format::Raw), Ok("MEETMEATTHEMALL".to_owned()));
+        let adfgvx = AdfgvxCipher::new("key", "word").unwrap();
+        assert!(!adfgvx.preserves_positions());
+        assert!(CaesarCipher::new(3).preserves_positions());
+    }
+
+    #[test]
+    fn ciphers_can_be_used_as_trait_objects() {
+        let ciphers: Vec<Box<dyn Cipher<Key = u32>>> = vec![
+            Box::new(MultiplicativeCipher::new(3).unwrap()),
+            Box::new(MultiplicativeCipher::new(5).unwrap()),
+        ];
+        assert_eq!(ciphers.iter().map(|c| c.key()).collect::<Vec<u32>>(), vec![3, 5]);
+        assert!(ciphers.iter().all(|c| c.name() == "Multiplicative"));
+    }
+}
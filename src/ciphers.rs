@@ -0,0 +1,23 @@
+//! All ciphers of the crate under short names.
+
+pub use crate::cipher::Cipher;
+pub use crate::error::CipherError;
+pub use crate::format::{Format, Grouping};
+pub use crate::fractionating::adfgvx::manipulations::AdfgvxCipher as Adfgvx;
+pub use crate::monoalphabetic::additive::caesar::manipulations::CaesarCipher as Caesar;
+pub use crate::monoalphabetic::affine::manipulations::AffineCipher as Affine;
+pub use crate::monoalphabetic::multiplicative::manipulations::MultiplicativeCipher as Multiplicative;
+pub use crate::monoalphabetic::substitution::manipulations::SubstitutionCipher as Substitution;
+pub use crate::polyalphabetic::four_square::manipulations::FourSquareCipher as FourSquare;
+pub use crate::polyalphabetic::hill::manipulations::HillCipher as Hill;
+pub use crate::polyalphabetic::hill_digraph::manipulations::HillDigraphCipher as HillDigraph;
+pub use crate::polyalphabetic::playfair::manipulations::PlayfairCipher as Playfair;
+pub use crate::polyalphabetic::two_square::manipulations::{Orientation, TwoSquareCipher as TwoSquare};
+pub use crate::polyalphabetic::vigenere::manipulations::VigenereCipher as Vigenere;
+pub use crate::polybius::PolybiusSquare;
+pub use crate::transposition::columnar::manipulations::ColumnarCipher as Columnar;
+pub use crate::transposition::double_columnar::manipulations::DoubleColumnarCipher as DoubleColumnar;
+pub use crate::transposition::geometric::rail_fence::manipulations::RailFenceCipher as RailFence;
+pub use crate::transposition::geometric::route::manipulations::{Route, RouteCipher};
+pub use crate::transposition::geometric::scytale::manipulations::ScytaleCipher as Scytale;
+pub use crate::transposition::myszkowski::manipulations::MyszkowskiCipher as Myszkowski;
@@ -1 +0,0 @@
-pub mod alphabet;
\ No newline at end of file
@@ -1 +0,0 @@
-pub mod functions;
\ No newline at end of file
@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong while creating, using or breaking a cipher
+#[derive(Debug, Clone, PartialEq)]
+pub enum CipherError {
+    /// The key has no inverse by the modulus, so the message couldn't be decrypted.
+    /// The value is the factor for the multiplicative and affine ciphers and the determinant for Hill.
+    NonInvertibleKey { value: i64, modulus: u32 },
+    /// The ciphertext can't be split into blocks of the cipher, like an odd-length Hill digraph ciphertext
+    InvalidLength { length: usize, block_size: usize },
+    /// The number of the values given for a matrix is not a square
+    NotSquareMatrix { length: usize },
+    EmptyInput,
+    CribTooLong { crib_length: usize, message_length: usize },
+    /// The crib fits in the message, but not from the position it was given at
+    CribOutOfMessage { crib_length: usize, position: usize, message_length: usize },
+    /// The crib or the known plaintext doesn't cover enough letters to find the key
+    CribTooShort { crib_length: usize, min_length: usize },
+    /// So many keys fit the known plaintext that they can't all be checked
+    TooManyKeys { count: usize, limit: usize },
+    SymbolNotInAlphabet(char),
+    InvalidAlphabet(String),
+    /// The key can't be used with the cipher, like a substitution key that isn't a permutation of the alphabet
+    InvalidKey(String),
+    /// A table of n-gram counts can't be read or parsed
+    InvalidCounts(String),
+    /// The cipher moves or adds letters, so the layout of the message can't be kept
+    UnsupportedFormat(&'static str),
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::NonInvertibleKey { value, modulus } => {
+                write!(f, "{} has no inverse by modulus {}, the key can't be used", value, modulus)
+            }
+            CipherError::InvalidLength { length, block_size } => write!(
+                f,
+                "Invalid length of the message: {} isn't a multiple of {}",
+                length, block_size
+            ),
+            CipherError::NotSquareMatrix { length } => {
+                write!(f, "{} values can't form a square matrix", length)
+            }
+            CipherError::EmptyInput => write!(f, "The input is empty"),
+            CipherError::CribTooLong {
+                crib_length,
+                message_length,
+            } => write!(
+                f,
+                "The crib ({} letters) is longer than the message ({} letters)",
+                crib_length, message_length
+            ),
+            CipherError::CribOutOfMessage {
+                crib_length,
+                position,
+                message_length,
+            } => write!(
+                f,
+                "The crib ({} letters) at position {} goes past the end of the message ({} letters)",
+                crib_length, position, message_length
+            ),
+            CipherError::CribTooShort { crib_length, min_length } => write!(
+                f,
+                "The crib ({} letters) is shorter than the {} letters needed",
+                crib_length, min_length
+            ),
+            CipherError::TooManyKeys { count, limit } => write!(
+                f,
+                "{} keys fit the known plaintext, more than the {} that can be checked",
+                count, limit
+            ),
+            CipherError::SymbolNotInAlphabet(symbol) => write!(f, "'{}' is not in the alphabet", symbol),
+            CipherError::InvalidAlphabet(reason) => write!(f, "Invalid alphabet: {}", reason),
+            CipherError::InvalidKey(reason) => write!(f, "Invalid key: {}", reason),
+            CipherError::InvalidCounts(reason) => write!(f, "Invalid n-gram counts: {}", reason),
+            CipherError::UnsupportedFormat(cipher) => {
+                write!(f, "{} moves or adds letters, the layout of the message can't be kept", cipher)
+            }
+        }
+    }
+}
+
+impl Error for CipherError {}
+
+#[cfg(test)]
+mod error_test {
+    use crate::error::CipherError;
+
+    #[test]
+    fn display_works() {
+        assert_eq!(
+            CipherError::NonInvertibleKey { value: 13, modulus: 26 }.to_string(),
+            "13 has no inverse by modulus 26, the key can't be used".to_owned()
+        );
+        assert_eq!(
+            CipherError::InvalidLength { length: 7, block_size: 2 }.to_string(),
+            "Invalid length of the message: 7 isn't a multiple of 2".to_owned()
+        );
+        assert_eq!(
+            CipherError::CribOutOfMessage { crib_length: 4, position: 2, message_length: 4 }.to_string(),
+            "The crib (4 letters) at position 2 goes past the end of the message (4 letters)".to_owned()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! How the output of the ciphers looks.
+
+use crate::alphabet::Alphabet;
+
+/// The layout of the output of `Cipher::encrypt_with` and `Cipher::decrypt_with`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Only the letters, uppercase and in groups of 5, so the word lengths are hidden
+    #[default]
+    Classical,
+    /// The case, spaces, digits and punctuation of the input are kept in place, only the letters
+    /// are changed. Letters added by the cipher (like the padding of Hill) go to the end.
+    /// Only for the ciphers whose `Cipher::preserves_positions` is true
+    Preserve,
+    /// Only the letters, uppercase and without spaces
+    Raw,
+}
+
+impl Format {
+    /// Lays out the letters of the output (everything else in it is skipped) by the input
+    pub fn apply(self, input: &str, output: &str, alphabet: &Alphabet) -> String {
+        let letters = alphabet.from_indices(&alphabet.to_indices(output));
+        match self {
+            Format::Classical => Grouping::default().format(&letters),
+            Format::Raw => letters,
+            Format::Preserve => preserve(input, &letters, alphabet),
+        }
+    }
+}
+
+/// Telegraphic layout of the ciphertext: the letters in groups, a number of groups per line,
+/// optionally with line numbers and the count of the groups in a 5-digit header.
+///
+/// ```
+/// use cryptology_for_beginners::format::Grouping;
+///
+/// let grouping = Grouping::new().with_groups_per_line(2).with_line_numbers(true).with_group_count(true);
+/// let text = grouping.format("WEAREDISCOVEREDFLEEATONCE");
+/// assert_eq!(text, "00005\n1  WEARE DISCO\n2  VERED FLEEA\n3  TONCE");
+/// assert_eq!(Grouping::parse(&text), "WEAREDISCOVEREDFLEEATONCE");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grouping {
+    group_size: usize,
+    groups_per_line: Option<usize>,
+    line_numbers: bool,
+    group_count: bool,
+}
+
+impl Default for Grouping {
+    /// Groups of 5 in one line, like `encrypt` gives them
+    fn default() -> Self {
+        Grouping {
+            group_size: 5,
+            groups_per_line: None,
+            line_numbers: false,
+            group_count: false,
+        }
+    }
+}
+
+impl Grouping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_group_size(mut self, group_size: usize) -> Self {
+        self.group_size = group_size.max(1);
+        self
+    }
+
+    /// All groups are in one line by default
+    pub fn with_groups_per_line(mut self, groups_per_line: usize) -> Self {
+        self.groups_per_line = Some(groups_per_line.max(1));
+        self
+    }
+
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// The first line is the count of the groups with 5 digits, so the receiver can check that nothing is lost
+    pub fn with_group_count(mut self, group_count: bool) -> Self {
+        self.group_count = group_count;
+        self
+    }
+
+    /// Lays out the symbols of the text, the whitespace in it is skipped
+    pub fn format(&self, text: &str) -> String {
+        let symbols = text.chars().filter(|c| !c.is_whitespace()).collect::<Vec<char>>();
+        let groups = symbols
+            .chunks(self.group_size)
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<String>>();
+        let mut lines = match self.groups_per_line {
+            Some(groups_per_line) => groups.chunks(groups_per_line).map(|line| line.join(" ")).collect(),
+            None => vec![groups.join(" ")],
+        };
+        if self.line_numbers {
+            let width = lines.len().to_string().len();
+            lines = lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>width$}  {}", i + 1, line, width = width))
+                .collect();
+        }
+        if self.group_count {
+            lines.insert(0, format!("{:05}", groups.len()));
+        }
+        lines.join("\n")
+    }
+
+    /// The symbols of a grouped text without the whitespace, the group count header and the line
+    /// numbers. Only the numbers laid out like `format` does are skipped: the header if it's the count
+    /// of the groups that follow, the line numbers if every line starts with its number from 1 and
+    /// two spaces, so groups of digits are kept.
+    pub fn parse(text: &str) -> String {
+        let lines = text.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<&str>>();
+        let groups = |lines: &[&str]| lines.iter().map(|line| line.split_whitespace().count()).sum::<usize>();
+        let lines = match lines.split_first() {
+            Some((header, rest)) if Self::is_group_count(header.trim(), groups(&without_line_numbers(rest))) => {
+                without_line_numbers(rest)
+            }
+            _ => without_line_numbers(&lines),
+        };
+        lines.iter().flat_map(|line| line.split_whitespace()).collect()
+    }
+
+    fn is_group_count(header: &str, groups: usize) -> bool {
+        header.len() == 5 && header.chars().all(|c| c.is_ascii_digit()) && header.parse() == Ok(groups)
+    }
+}
+
+/// The lines without their numbers if all of them are numbered from 1 like `Grouping::format` does it
+fn without_line_numbers<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let numbered = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| line.trim_start().strip_prefix(&(i + 1).to_string()).and_then(|rest| rest.strip_prefix("  ")))
+        .collect::<Option<Vec<&str>>>();
+    numbered.unwrap_or_else(|| lines.to_vec())
+}
+
+/// Every letter of the input is replaced by the next letter of the output in the case of the input letter
+fn preserve(input: &str, letters: &str, alphabet: &Alphabet) -> String {
+    let mut letters = letters.chars();
+    let mut result = String::new();
+    for c in input.chars() {
+        // Letters like the Armenian "և" are two letters of the alphabet
+        let count = alphabet.to_indices(&c.to_string()).len();
+        if count == 0 {
+            result.push(c);
+            continue;
+        }
+        for letter in letters.by_ref().take(count) {
+            if c.is_lowercase() {
+                result.extend(letter.to_lowercase());
+            } else {
+                result.push(letter);
+            }
+        }
+    }
+    result.extend(letters);
+    result
+}
+
+#[cfg(test)]
+mod format_test {
+    use crate::alphabet::Alphabet;
+    use crate::format::{Format, Grouping};
+
+    #[test]
+    fn apply_works() {
+        let alphabet = Alphabet::english();
+        let input = "Hello, World! 42";
+        let output = "KHOOR ZRUOG";
+        assert_eq!(Format::Classical.apply(input, output, &alphabet), "KHOOR ZRUOG");
+        assert_eq!(Format::Raw.apply(input, output, &alphabet), "KHOORZRUOG");
+        assert_eq!(Format::Preserve.apply(input, output, &alphabet), "Khoor, Zruog! 42");
+    }
+
+    #[test]
+    fn preserve_appends_extra_letters() {
+        assert_eq!(Format::Preserve.apply("Hi!", "ABC", &Alphabet::english()), "Ab!C");
+        assert_eq!(Format::Preserve.apply("Ես և դու", "ԱԲԳԴԵԶԷ", &Alphabet::armenian()), "Աբ գդ եզէ");
+    }
+
+    #[test]
+    fn grouping_works() {
+        let letters = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+        assert_eq!(Grouping::default().format(letters), "THEQU ICKBR OWNFO XJUMP SOVER THELA ZYDOG");
+        assert_eq!(Grouping::new().with_group_size(4).format("ABCD EFGH IJ"), "ABCD EFGH IJ");
+        let grouping = Grouping::new()
+            .with_group_size(3)
+            .with_groups_per_line(1)
+            .with_line_numbers(true)
+            .with_group_count(true);
+        let text = grouping.format(letters);
+        assert!(text.starts_with("00012\n 1  THE\n 2  QUI\n"));
+        assert!(text.ends_with("\n11  ZYD\n12  OG"));
+        assert_eq!(Grouping::new().with_group_count(true).format(""), "00000\n");
+    }
+
+    #[test]
+    fn parse_works() {
+        assert_eq!(Grouping::parse("00002\n 1  THEQU\n 2  ICKBR\n"), "THEQUICKBR");
+        assert_eq!(Grouping::parse("THEQU ICKBR\n\nOWNFO\r\nXJUMP"), "THEQUICKBROWNFOXJUMP");
+        // The numbers that aren't laid out like format does it are a part of the text
+        assert_eq!(Grouping::parse("ABC\n12\n"), "ABC12");
+        assert_eq!(Grouping::parse("12345 ABCDE"), "12345ABCDE");
+        assert_eq!(Grouping::parse("00003\nABCDE FGHIJ"), "00003ABCDEFGHIJ");
+        assert_eq!(Grouping::parse("1  ABCDE\n3  FGHIJ"), "1ABCDE3FGHIJ");
+    }
+
+    #[test]
+    fn parse_reads_digit_groups_back() {
+        // Like the ciphertext of a 6x6 Polybius square with the digits
+        let text = "12345ABCDE67890FGHIJ0123456789KLMNOPQRST00001";
+        let groupings = [
+            Grouping::default(),
+            Grouping::new().with_groups_per_line(1),
+            Grouping::new().with_groups_per_line(2).with_line_numbers(true),
+            Grouping::new().with_groups_per_line(1).with_line_numbers(true).with_group_count(true),
+            Grouping::new().with_group_size(3).with_group_count(true),
+        ];
+        for grouping in groupings.iter() {
+            assert_eq!(Grouping::parse(&grouping.format(text)), text, "{:?}", grouping);
+        }
+    }
+}
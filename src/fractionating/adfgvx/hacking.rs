@@ -0,0 +1,227 @@
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, Annealing};
+use crate::error::CipherError;
+use crate::fractionating::adfgvx::manipulations::{AdfgvxKey, ADFGVX, ADFGX};
+use crate::monoalphabetic::substitution::hacking::SubstitutionHack;
+use crate::polybius::search::square_scorer;
+use crate::polybius::{alphanumeric_alphabet, merged_alphabet};
+use crate::scoring::frequencies::index_of_coincidence;
+use crate::scoring::Scorer;
+use crate::transposition::columnar::hacking::ColumnarHack;
+use crate::transposition::untranspose;
+
+/// How many of the best orders of the transposition get their substitution solved
+const SOLVED_ORDERS: usize = 1;
+
+/// Breaks ADFGVX (and ADFGX) in two steps. First the order of the transposition is found with
+/// `ColumnarHack`, scored by the frequencies of the pairs of the letters instead of a language:
+/// with the right order the letters that were written together are paired again, and the pairs
+/// (the cells of the square) are as uneven as the letters of the language, the pairs of neighbouring
+/// cells too. Then the cells are letters of a substitution cipher, which `SubstitutionHack` breaks.
+///
+/// Both steps need long messages, a few hundred letters of the encrypted message at least.
+pub struct AdfgvxHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    min_key_length: usize,
+    max_key_length: usize,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+}
+
+impl AdfgvxHack {
+    /// ADFGVX with the 6×6 square of the letters and the digits
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, alphanumeric_alphabet())
+    }
+
+    /// ADFGX with the 5×5 square, J is written as I
+    pub fn adfgx(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, merged_alphabet('J', 'I').unwrap())
+    }
+
+    /// The alphabet of the square, 25 symbols for ADFGX or 36 for ADFGVX
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        AdfgvxHack {
+            encrypted_message,
+            alphabet: alphabet.one_based(false),
+            min_key_length: 2,
+            max_key_length: 10,
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+        }
+    }
+
+    /// The longest key of the transposition that is tried, 10 by default
+    pub fn with_max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = max_key_length.max(2);
+        self
+    }
+
+    /// Only the given key length of the transposition is tried
+    pub fn with_key_length(mut self, key_length: usize) -> Self {
+        self.min_key_length = key_length.max(2);
+        self.max_key_length = key_length.max(2);
+        self
+    }
+
+    annealing_builders!(3, 100000);
+
+    /// The best order of the transposition for every key length (as the numbers of the columns)
+    /// with its score, the frequency of the same pairs and of the same neighbouring pairs
+    /// of the letters, the best one first
+    pub fn get_transposition_orders(&self) -> Result<Vec<(Vec<usize>, f64)>, CipherError> {
+        let header = self.header()?;
+        let letters = header.to_indices_strict(&self.encrypted_message)?;
+        if letters.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        if !letters.len().is_multiple_of(2) {
+            return Err(CipherError::InvalidLength {
+                length: letters.len(),
+                block_size: 2,
+            });
+        }
+        let size = header.modulus() as usize;
+        let pairs_header = header.clone();
+        let pairs_scorer = move |message: &str| {
+            let letters = pairs_header.to_indices(message);
+            let cells = letters.chunks_exact(2).map(|pair| pair[0] * size as u32 + pair[1]).collect::<Vec<u32>>();
+            let neighbours = cells.windows(2).map(|pair| pair[0] * (size * size) as u32 + pair[1]).collect::<Vec<u32>>();
+            // Compared with random pairs, which have the same frequency
+            let cells_coincidence = index_of_coincidence(&cells).unwrap_or(0.0) * (size * size) as f64;
+            let neighbours_coincidence = index_of_coincidence(&neighbours).unwrap_or(0.0) * (size * size * size * size) as f64;
+            cells_coincidence + neighbours_coincidence
+        };
+        let hack = ColumnarHack::with_alphabet(self.encrypted_message.clone(), header)
+            .with_max_key_length(self.max_key_length)
+            .with_scorer(pairs_scorer)
+            .with_seed(self.annealing.seed)
+            .with_restarts(self.annealing.restarts);
+        let hack = if self.min_key_length == self.max_key_length {
+            hack.with_key_length(self.min_key_length)
+        } else {
+            hack
+        };
+        Ok(hack
+            .get_ranked_variants()
+            .into_iter()
+            .map(|(order, _, score)| (order, score))
+            .collect())
+    }
+
+    /// The square and the order of the transposition found from the best orders with the decrypted message
+    /// and its score, the best one first. The cells of the square that aren't in the message are guessed
+    pub fn get_ranked_variants(&self) -> Result<Vec<(AdfgvxKey, String, f64)>, CipherError> {
+        let header = self.header()?;
+        let size = header.modulus() as usize;
+        let default = square_scorer(&self.alphabet);
+        // The quadgrams skip the digits, so the substitution would hide the rare letters in the cells
+        // of the digits: they are scored as Q instead, which hardly makes any quadgram
+        let letters_scorer = |message: &str| default.score(&message.replace(|c: char| c.is_ascii_digit(), "Q"));
+        let scorer: &dyn Scorer = match &self.scorer {
+            Some(scorer) => scorer.as_ref(),
+            None => &letters_scorer,
+        };
+        let symbols = self.alphabet.symbols();
+        let mut variants = vec![];
+        for (order, _) in self.get_transposition_orders()?.into_iter().take(SOLVED_ORDERS) {
+            // The cells of the square as the symbols of the alphabet in order, so the substitution gives the square
+            let letters = header.to_indices(&self.encrypted_message);
+            let ranks = order.iter().map(|x| x - 1).collect::<Vec<usize>>();
+            let cells = untranspose(&letters, &ranks)
+                .chunks_exact(2)
+                .map(|pair| symbols[pair[0] as usize * size + pair[1] as usize])
+                .collect::<String>();
+            let substitution = SubstitutionHack::with_alphabet(cells, self.alphabet.clone())
+                .with_seed(self.annealing.seed)
+                .with_restarts(self.annealing.restarts)
+                .with_iterations(self.annealing.iterations);
+            if let Some((key, message, score)) = substitution.ranked_variants_with(scorer).into_iter().next() {
+                // The key has the cell of every symbol
+                let mut square = vec![' '; symbols.len()];
+                for (symbol, cell) in symbols.iter().zip(key.chars()) {
+                    square[symbols.iter().position(|s| *s == cell).unwrap()] = *symbol;
+                }
+                variants.push(((square.into_iter().collect::<String>(), order), message, score));
+            }
+        }
+        variants.sort_by(|a, b| b.2.total_cmp(&a.2));
+        Ok(variants)
+    }
+
+    pub fn best(&self) -> Result<(AdfgvxKey, String, f64), CipherError> {
+        self.get_ranked_variants()?.into_iter().next().ok_or(CipherError::EmptyInput)
+    }
+
+    /// The letters of the rows and the columns of the square
+    fn header(&self) -> Result<Alphabet, CipherError> {
+        match self.alphabet.modulus() {
+            25 => Ok(Alphabet::new(ADFGX).unwrap()),
+            36 => Ok(Alphabet::new(ADFGVX).unwrap()),
+            symbols => Err(CipherError::InvalidAlphabet(format!(
+                "{} symbols can't fill a 5×5 or a 6×6 square",
+                symbols
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod adfgvx_hack_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::fractionating::adfgvx::hacking::AdfgvxHack;
+    use crate::fractionating::adfgvx::manipulations::AdfgvxCipher;
+    use crate::polybius::{alphanumeric_alphabet, merged_alphabet, PolybiusSquare};
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way";
+
+    #[test]
+    fn adfgvx_is_broken() {
+        let square = PolybiusSquare::random(3, alphanumeric_alphabet()).unwrap();
+        let cipher = AdfgvxCipher::with_square(square, &[4, 1, 6, 3, 2, 5]).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        for seed in 10..13 {
+            let hack = AdfgvxHack::new(encrypted.clone()).with_key_length(6).with_seed(seed);
+            let ((square, order), message, _) = hack.best().unwrap();
+            assert_eq!(order, vec![4, 1, 6, 3, 2, 5]);
+            assert_eq!(message, cipher.decrypt(encrypted.clone()).unwrap(), "seed {}", seed);
+            let square = PolybiusSquare::with_alphabet(&square, alphanumeric_alphabet()).unwrap();
+            assert_eq!(AdfgvxCipher::with_square(square, &order).unwrap().decrypt(encrypted.clone()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn defaults_break_adfgvx() {
+        let square = PolybiusSquare::random(3, alphanumeric_alphabet()).unwrap();
+        let cipher = AdfgvxCipher::with_square(square, &[4, 1, 6, 3, 2, 5]).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        let ((_, order), message, _) = AdfgvxHack::new(encrypted.clone()).best().unwrap();
+        assert_eq!(order, vec![4, 1, 6, 3, 2, 5]);
+        assert_eq!(message, cipher.decrypt(encrypted).unwrap());
+    }
+
+    #[test]
+    fn adfgx_is_broken() {
+        let square = PolybiusSquare::random(5, merged_alphabet('J', 'I').unwrap()).unwrap();
+        let cipher = AdfgvxCipher::with_square(square, &[3, 5, 1, 4, 2]).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        for seed in 10..13 {
+            let hack = AdfgvxHack::adfgx(encrypted.clone()).with_key_length(5).with_seed(seed);
+            let (_, message, _) = hack.best().unwrap();
+            assert_eq!(message, cipher.decrypt(encrypted.clone()).unwrap(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(AdfgvxHack::new("".to_owned()).best(), Err(CipherError::EmptyInput));
+        assert_eq!(AdfgvxHack::new("ADF".to_owned()).best(), Err(CipherError::InvalidLength { length: 3, block_size: 2 }));
+        assert_eq!(AdfgvxHack::adfgx("ADFV".to_owned()).best(), Err(CipherError::SymbolNotInAlphabet('V')));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::polybius::PolybiusSquare;
+use crate::transposition::{keyword_ranks, order_ranks, to_order, transpose, untranspose};
+
+/// The letters that name the rows and the columns of the 5×5 and the 6×6 squares
+pub const ADFGX: &str = "ADFGX";
+pub const ADFGVX: &str = "ADFGVX";
+
+/// The square row by row and the numbers of the columns of the transposition in the reading order
+pub type AdfgvxKey = (String, Vec<usize>);
+
+/// Every letter is replaced by the names of its row and column in a keyed Polybius square
+/// (A, D, F, G, X for the 5×5 square of ADFGX, A, D, F, G, V, X for the 6×6 square of ADFGVX with
+/// the letters and the digits), then the pairs are split by a columnar transposition with an
+/// incomplete last row.
+///
+/// ```
+/// use cryptology_for_beginners::cipher::Cipher;
+/// use cryptology_for_beginners::fractionating::adfgvx::manipulations::AdfgvxCipher;
+///
+/// let cipher = AdfgvxCipher::adfgx("btalpdhozkqfvsngicuxmrewy", "cargo").unwrap();
+/// assert_eq!(cipher.encrypt("Attack at once".to_owned()), "FAXDF ADDDG DGFFF AFAXA FAFX");
+/// ```
+pub struct AdfgvxCipher {
+    square: PolybiusSquare,
+    // The rank of every column of the transposition from 0
+    ranks: Vec<usize>,
+    header: Alphabet,
+}
+
+impl AdfgvxCipher {
+    /// ADFGVX with the 6×6 square of the letters and the digits of the square keyword
+    /// and the transposition keyword
+    pub fn new(square_keyword: &str, keyword: &str) -> Result<Self, CipherError> {
+        let square = PolybiusSquare::alphanumeric(square_keyword)?;
+        let ranks = keyword_ranks(keyword, &Alphabet::english(), false)?;
+        Self::with_square(square, &to_order(&ranks))
+    }
+
+    /// ADFGX with the 5×5 square of the square keyword (J is written as I) and the transposition keyword
+    pub fn adfgx(square_keyword: &str, keyword: &str) -> Result<Self, CipherError> {
+        let square = PolybiusSquare::from_keyword(square_keyword)?;
+        let ranks = keyword_ranks(keyword, &Alphabet::english(), false)?;
+        Self::with_square(square, &to_order(&ranks))
+    }
+
+    /// Any 5×5 or 6×6 square and the transposition as the numbers of the columns in the reading order,
+    /// like `ColumnarCipher::from_order`
+    pub fn with_square(square: PolybiusSquare, order: &[usize]) -> Result<Self, CipherError> {
+        let header = match square.size() {
+            5 => ADFGX,
+            6 => ADFGVX,
+            size => {
+                return Err(CipherError::InvalidKey(format!(
+                    "the square should be 5×5 or 6×6, not {}×{}",
+                    size, size
+                )))
+            }
+        };
+        Ok(AdfgvxCipher {
+            square,
+            ranks: order_ranks(order, false)?,
+            header: Alphabet::new(header).unwrap(),
+        })
+    }
+
+    pub fn square(&self) -> &PolybiusSquare {
+        &self.square
+    }
+}
+
+impl Cipher for AdfgvxCipher {
+    type Key = AdfgvxKey;
+
+    fn name(&self) -> &'static str {
+        if self.square.size() == 5 {
+            "ADFGX"
+        } else {
+            "ADFGVX"
+        }
+    }
+
+    fn key(&self) -> AdfgvxKey {
+        (self.square.key(), to_order(&self.ranks))
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        self.square.alphabet()
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let size = self.square.size();
+        let coordinates = self
+            .square
+            .indices(&message)
+            .into_iter()
+            .flat_map(|x| {
+                let cell = self.square.positions()[x];
+                [(cell / size) as u32, (cell % size) as u32]
+            })
+            .collect::<Vec<u32>>();
+        Grouping::default().format(&self.header.from_indices(&transpose(&coordinates, &self.ranks)))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let coordinates = untranspose(&self.header.to_indices_strict(&encrypted_message)?, &self.ranks);
+        if !coordinates.len().is_multiple_of(2) {
+            return Err(CipherError::InvalidLength {
+                length: coordinates.len(),
+                block_size: 2,
+            });
+        }
+        let size = self.square.size();
+        let symbols = self.square.alphabet().symbols();
+        Ok(coordinates
+            .chunks_exact(2)
+            .map(|pair| symbols[self.square.cells()[pair[0] as usize * size + pair[1] as usize]])
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod adfgvx_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::fractionating::adfgvx::manipulations::AdfgvxCipher;
+    use crate::polybius::{alphanumeric_alphabet, PolybiusSquare};
+
+    #[test]
+    fn adfgx_works() {
+        let cipher = AdfgvxCipher::adfgx("btalpdhozkqfvsngicuxmrewy", "cargo").unwrap();
+        assert_eq!(cipher.name(), "ADFGX");
+        let encrypted = cipher.encrypt("Attack at once".to_owned());
+        assert_eq!(encrypted, "FAXDF ADDDG DGFFF AFAXA FAFX");
+        assert_eq!(cipher.decrypt(encrypted), Ok("ATTACKATONCE".to_owned()));
+    }
+
+    #[test]
+    fn adfgvx_works() {
+        let square = PolybiusSquare::with_alphabet("na1c3h8tb2ome5wrpd4f6g7i9j0klqsuvxyz", alphanumeric_alphabet()).unwrap();
+        let cipher = AdfgvxCipher::with_square(square, &[4, 5, 3, 6, 1, 2, 7]).unwrap();
+        assert_eq!(cipher.name(), "ADFGVX");
+        let encrypted = cipher.encrypt("Attack at 1200AM".to_owned());
+        assert_eq!(encrypted, "DGDDD AGDDG AFADD FDADV DVFAA DVX");
+        assert_eq!(cipher.decrypt(encrypted), Ok("ATTACKAT1200AM".to_owned()));
+        let cipher = AdfgvxCipher::new("na1c3h8tb2ome5wrpd4f6g7i9j0klqsuvxyz", "privacy").unwrap();
+        assert_eq!(cipher.key().1, vec![4, 5, 3, 6, 1, 2, 7]);
+        assert_eq!(cipher.encrypt("Attack at 1200AM".to_owned()), "DGDDD AGDDG AFADD FDADV DVFAA DVX");
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let cipher = AdfgvxCipher::new("key", "word").unwrap();
+        assert_eq!(cipher.decrypt("ADFGB".to_owned()), Err(CipherError::SymbolNotInAlphabet('B')));
+        assert_eq!(
+            cipher.decrypt("ADF".to_owned()),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+        let square = PolybiusSquare::with_alphabet("abcd", Alphabet::new("ABCD").unwrap()).unwrap();
+        assert_eq!(
+            AdfgvxCipher::with_square(square, &[1]).err(),
+            Some(CipherError::InvalidKey("the square should be 5×5 or 6×6, not 2×2".to_owned()))
+        );
+    }
+}
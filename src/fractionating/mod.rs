@@ -0,0 +1,4 @@
+//! Fractionating ciphers, which split every letter into parts (the coordinates of a Polybius square)
+//! and transpose the parts, so the letters of the encrypted message don't stand for whole letters.
+
+pub mod adfgvx;
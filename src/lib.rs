@@ -1,3 +1,39 @@
-mod polyalphabetic;
-mod constants;
-mod monoalphabetic;
\ No newline at end of file
+//! Classical ciphers and the tools to break them, written while following
+//! "Cryptology for beginners".
+//!
+//! The ciphers are available from [`ciphers`] and all of them implement the [`Cipher`](cipher::Cipher)
+//! trait, the cryptanalysis helpers are available from [`attacks`]. The hacks rank their candidates
+//! with the scorers from [`scoring`], [`analysis`] has the frequency statistics of messages and
+//! [`segment`] splits the decrypted messages into words.
+//!
+//! ```
+//! use cryptology_for_beginners::attacks::CaesarHack;
+//! use cryptology_for_beginners::ciphers::{Caesar, Cipher};
+//!
+//! let encrypted = Caesar::new(8).encrypt("I'm hungry, let's get a pizza".to_owned());
+//! assert_eq!(encrypted, "QUPCV OZGTM BAOMB IXQHH I");
+//! assert!(CaesarHack::new(encrypted)
+//!     .get_all_variants()
+//!     .contains(&(8, "IMHUNGRYLETSGETAPIZZA".to_owned())));
+//! ```
+//!
+//! The ciphers themselves live in [`monoalphabetic`], [`polyalphabetic`], [`transposition`] and
+//! [`fractionating`], next to their `hacking` modules, the keyed squares of the digraphic ciphers are in [`polybius`].
+
+pub mod alphabet;
+pub mod analysis;
+mod annealing;
+pub mod attacks;
+pub mod cipher;
+pub mod ciphers;
+pub mod error;
+pub mod format;
+pub mod fractionating;
+pub mod modular;
+pub mod monoalphabetic;
+pub mod polyalphabetic;
+pub mod polybius;
+pub mod random;
+pub mod scoring;
+pub mod segment;
+pub mod transposition;
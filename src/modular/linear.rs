@@ -0,0 +1,176 @@
+use crate::modular::{crt, inverse, prime_factors};
+
+/// All solutions of the linear system `coefficients * x = values` by the modulus.
+///
+/// The system is solved by every prime power of the modulus separately (by a prime with Gaussian
+/// elimination, then lifted to its powers), and the solutions are combined with the Chinese
+/// remainder theorem. So for 26 the solutions by 2 and by 13 are found and combined.
+pub fn solve(coefficients: &[Vec<i64>], values: &[i64], modulus: i64) -> Vec<Vec<i64>> {
+    let unknowns = coefficients.first().map_or(0, |row| row.len());
+    prime_factors(modulus)
+        .into_iter()
+        .map(|(prime, power)| {
+            let prime_power = prime.pow(power);
+            let solutions = solve_by_prime_power(coefficients, values, prime, power);
+            (solutions, prime_power)
+        })
+        .fold(vec![(vec![0; unknowns], 1)], |combined, (solutions, prime_power)| {
+            combined
+                .iter()
+                .flat_map(|(x, combined_modulus)| {
+                    solutions.iter().map(move |y| {
+                        let value = x
+                            .iter()
+                            .zip(y)
+                            .map(|(a, b)| crt(&[(*a, *combined_modulus), (*b, prime_power)]).unwrap())
+                            .collect();
+                        (value, combined_modulus * prime_power)
+                    })
+                })
+                .collect()
+        })
+        .into_iter()
+        .map(|(x, _)| x)
+        .collect()
+}
+
+/// Solves by the prime, then lifts every solution by p^(k-1) to p^k
+/// as x + p^(k-1) * t, where t is solved by the prime again
+fn solve_by_prime_power(coefficients: &[Vec<i64>], values: &[i64], prime: i64, power: u32) -> Vec<Vec<i64>> {
+    let mut solutions = solve_by_prime(coefficients, values, prime);
+    for k in 2..=power {
+        let previous_modulus = prime.pow(k - 1);
+        let modulus = prime.pow(k);
+        solutions = solutions
+            .into_iter()
+            .flat_map(|x| {
+                let residuals = coefficients
+                    .iter()
+                    .zip(values)
+                    .map(|(row, value)| (value - dot(row, &x, modulus)).rem_euclid(modulus) / previous_modulus)
+                    .collect::<Vec<i64>>();
+                solve_by_prime(coefficients, &residuals, prime)
+                    .into_iter()
+                    .map(move |t| {
+                        x.iter()
+                            .zip(t)
+                            .map(|(a, b)| (a + previous_modulus * b).rem_euclid(modulus))
+                            .collect()
+                    })
+            })
+            .collect();
+    }
+    solutions
+}
+
+/// Gaussian elimination in the field of the prime, all solutions are enumerated from the free variables
+fn solve_by_prime(coefficients: &[Vec<i64>], values: &[i64], prime: i64) -> Vec<Vec<i64>> {
+    let unknowns = coefficients.first().map_or(0, |row| row.len());
+    let mut rows = coefficients
+        .iter()
+        .zip(values)
+        .map(|(row, value)| {
+            let mut row = row.iter().map(|x| x.rem_euclid(prime)).collect::<Vec<i64>>();
+            row.push(value.rem_euclid(prime));
+            row
+        })
+        .collect::<Vec<Vec<i64>>>();
+    let mut pivot_columns = vec![];
+    for column in 0..unknowns {
+        let current = pivot_columns.len();
+        let pivot_row = match (current..rows.len()).find(|row| rows[*row][column] != 0) {
+            Some(row) => row,
+            None => continue,
+        };
+        rows.swap(current, pivot_row);
+        let pivot_inverse = inverse(rows[current][column], prime).unwrap();
+        rows[current] = rows[current].iter().map(|x| x * pivot_inverse % prime).collect();
+        for row in (0..rows.len()).filter(|row| *row != current) {
+            let factor = rows[row][column];
+            rows[row] = rows[row]
+                .iter()
+                .zip(&rows[current])
+                .map(|(x, y)| (x - factor * y).rem_euclid(prime))
+                .collect();
+        }
+        pivot_columns.push(column);
+    }
+    // 0 = non-zero rows mean there are no solutions
+    if rows[pivot_columns.len()..].iter().any(|row| row[unknowns] != 0) {
+        return vec![];
+    }
+    let free_columns = (0..unknowns)
+        .filter(|column| !pivot_columns.contains(column))
+        .collect::<Vec<usize>>();
+    let count = prime.pow(free_columns.len() as u32);
+    (0..count)
+        .map(|mut assignment| {
+            let mut solution = vec![0; unknowns];
+            for column in &free_columns {
+                solution[*column] = assignment % prime;
+                assignment /= prime;
+            }
+            for (row, column) in pivot_columns.iter().enumerate() {
+                let free_part = free_columns
+                    .iter()
+                    .map(|free| rows[row][*free] * solution[*free])
+                    .sum::<i64>();
+                solution[*column] = (rows[row][unknowns] - free_part).rem_euclid(prime);
+            }
+            solution
+        })
+        .collect()
+}
+
+fn dot(a: &[i64], b: &[i64], modulus: i64) -> i64 {
+    a.iter().zip(b).map(|(x, y)| x * y % modulus).sum::<i64>() % modulus
+}
+
+#[cfg(test)]
+mod linear_test {
+    use crate::modular::linear::solve;
+
+    fn check(coefficients: &[Vec<i64>], values: &[i64], modulus: i64, solutions: &[Vec<i64>]) {
+        for solution in solutions {
+            for (row, value) in coefficients.iter().zip(values) {
+                let left = row.iter().zip(solution).map(|(a, x)| a * x).sum::<i64>();
+                assert_eq!(left.rem_euclid(modulus), value.rem_euclid(modulus));
+            }
+        }
+    }
+
+    #[test]
+    fn unique_solution() {
+        let coefficients = vec![vec![3, 5], vec![1, 2]];
+        let solutions = solve(&coefficients, &[10, 3], 26);
+        assert_eq!(solutions, vec![vec![5, 25]]);
+        check(&coefficients, &[10, 3], 26, &solutions);
+    }
+
+    #[test]
+    fn multiple_solutions_by_composite_modulus() {
+        // The determinant is 2, so there are 2 solutions by 26
+        let coefficients = vec![vec![2, 0], vec![0, 1]];
+        let solutions = solve(&coefficients, &[4, 7], 26);
+        assert_eq!(solutions, vec![vec![2, 7], vec![15, 7]]);
+        check(&coefficients, &[4, 7], 26, &solutions);
+    }
+
+    #[test]
+    fn no_solutions() {
+        assert!(solve(&[vec![2, 4]], &[1], 26).is_empty());
+    }
+
+    #[test]
+    fn prime_powers_are_lifted() {
+        let coefficients = vec![vec![2, 3], vec![4, 1]];
+        let solutions = solve(&coefficients, &[7, 5], 36);
+        check(&coefficients, &[7, 5], 36, &solutions);
+        assert_eq!(solutions, vec![vec![8, 9], vec![26, 9]]);
+        let brute_force = (0..36 * 36)
+            .map(|i| vec![i / 36, i % 36])
+            .filter(|x| (2 * x[0] + 3 * x[1]) % 36 == 7 && (4 * x[0] + x[1]) % 36 == 5)
+            .count();
+        assert_eq!(solutions.len(), brute_force);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::error::CipherError;
+use crate::modular::{inverse, is_unit};
+
+/// Square matrix with the values stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    size: usize,
+    values: Vec<i64>,
+}
+
+impl Matrix {
+    /// Creates the matrix from its values row by row, the size is found from their count
+    pub fn new(values: Vec<i64>) -> Result<Self, CipherError> {
+        let size = (values.len() as f64).sqrt().round() as usize;
+        if values.is_empty() || size * size != values.len() {
+            return Err(CipherError::NotSquareMatrix { length: values.len() });
+        }
+        Ok(Matrix { size, values })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<i64>>) -> Result<Self, CipherError> {
+        let length = rows.iter().map(|row| row.len()).sum();
+        if rows.iter().any(|row| row.len() != rows.len()) {
+            return Err(CipherError::NotSquareMatrix { length });
+        }
+        Self::new(rows.into_iter().flatten().collect())
+    }
+
+    pub fn identity(size: usize) -> Self {
+        Matrix {
+            size,
+            values: (0..size * size).map(|i| (i / size == i % size) as i64).collect(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> i64 {
+        self.values[row * self.size + column]
+    }
+
+    pub fn row(&self, row: usize) -> &[i64] {
+        &self.values[row * self.size..(row + 1) * self.size]
+    }
+
+    /// The same matrix with all values in 0..modulus
+    pub fn reduce(&self, modulus: i64) -> Self {
+        Matrix {
+            size: self.size,
+            values: self.values.iter().map(|x| x.rem_euclid(modulus)).collect(),
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Matrix {
+            size: self.size,
+            values: (0..self.size * self.size)
+                .map(|i| self.get(i % self.size, i / self.size))
+                .collect(),
+        }
+    }
+
+    /// The matrix without the given row and column
+    pub fn minor(&self, row: usize, column: usize) -> Self {
+        Matrix {
+            size: self.size - 1,
+            values: (0..self.size * self.size)
+                .filter(|i| i / self.size != row && i % self.size != column)
+                .map(|i| self.values[i])
+                .collect(),
+        }
+    }
+
+    /// Determinant by the modulus, found by cofactor expansion along the first row
+    pub fn determinant(&self, modulus: i64) -> i64 {
+        match self.size {
+            0 => 1 % modulus,
+            1 => self.values[0].rem_euclid(modulus),
+            _ => (0..self.size)
+                .map(|column| {
+                    let sign = if column % 2 == 0 { 1 } else { -1 };
+                    sign * self.get(0, column).rem_euclid(modulus) * self.minor(0, column).determinant(modulus)
+                })
+                .sum::<i64>()
+                .rem_euclid(modulus),
+        }
+    }
+
+    /// The transposed matrix of the cofactors
+    pub fn adjugate(&self, modulus: i64) -> Self {
+        if self.size == 1 {
+            return Self::identity(1).reduce(modulus);
+        }
+        Matrix {
+            size: self.size,
+            values: (0..self.size * self.size)
+                .map(|i| {
+                    let (row, column) = (i / self.size, i % self.size);
+                    let sign = if (row + column) % 2 == 0 { 1 } else { -1 };
+                    // Transposing while calculating
+                    (sign * self.minor(column, row).determinant(modulus)).rem_euclid(modulus)
+                })
+                .collect(),
+        }
+    }
+
+    /// The inverse matrix by the modulus, exists only when the determinant is coprime with the modulus.
+    /// Found with the Gauss-Jordan elimination
+    pub fn inverse(&self, modulus: i64) -> Result<Self, CipherError> {
+        let determinant = self.determinant(modulus);
+        if !is_unit(determinant, modulus) {
+            return Err(CipherError::NonInvertibleKey {
+                value: determinant,
+                modulus: modulus as u32,
+            });
+        }
+        let n = self.size;
+        // Every row is the row of the matrix followed by the row of the identity matrix
+        let mut rows = (0..n)
+            .map(|row| {
+                let mut values = self.reduce(modulus).row(row).to_vec();
+                values.extend(Self::identity(n).row(row));
+                values
+            })
+            .collect::<Vec<Vec<i64>>>();
+        for column in 0..n {
+            let pivot_row = Self::reduce_column(&mut rows, column, modulus);
+            rows.swap(column, pivot_row);
+            // The pivot is a unit as the determinant is a unit
+            let pivot_inverse = inverse(rows[column][column], modulus).unwrap();
+            rows[column] = rows[column].iter().map(|x| x * pivot_inverse % modulus).collect();
+            for row in (0..n).filter(|row| *row != column) {
+                let factor = rows[row][column];
+                rows[row] = Self::subtract_row(&rows[row], &rows[column], factor, modulus);
+            }
+        }
+        Self::from_rows(rows.into_iter().map(|row| row[n..].to_vec()).collect())
+    }
+
+    /// Leaves only one row from `column` downwards with a non-zero value in the column, like the
+    /// Euclidean algorithm does (as the values might have no inverses by a composite modulus).
+    /// Returns that row.
+    fn reduce_column(rows: &mut [Vec<i64>], column: usize, modulus: i64) -> usize {
+        loop {
+            let non_zero = (column..rows.len())
+                .filter(|row| rows[*row][column] != 0)
+                .collect::<Vec<usize>>();
+            let pivot_row = *non_zero.iter().min_by_key(|row| rows[**row][column]).unwrap();
+            if non_zero.len() == 1 {
+                return pivot_row;
+            }
+            for row in non_zero.into_iter().filter(|row| *row != pivot_row) {
+                let factor = rows[row][column] / rows[pivot_row][column];
+                rows[row] = Self::subtract_row(&rows[row], &rows[pivot_row], factor, modulus);
+            }
+        }
+    }
+
+    fn subtract_row(row: &[i64], other: &[i64], factor: i64, modulus: i64) -> Vec<i64> {
+        row.iter()
+            .zip(other)
+            .map(|(x, y)| (x - factor * y).rem_euclid(modulus))
+            .collect()
+    }
+
+    pub fn multiply_by_number(&self, factor: i64, modulus: i64) -> Self {
+        Matrix {
+            size: self.size,
+            values: self.values.iter().map(|x| (x * factor).rem_euclid(modulus)).collect(),
+        }
+    }
+
+    pub fn multiply(&self, other: &Matrix, modulus: i64) -> Self {
+        let other = other.transpose();
+        Matrix {
+            size: self.size,
+            values: (0..self.size * self.size)
+                .map(|i| Self::dot(self.row(i / self.size), other.row(i % self.size), modulus))
+                .collect(),
+        }
+    }
+
+    /// The matrix multiplied by the column vector
+    pub fn multiply_vector(&self, vector: &[i64], modulus: i64) -> Vec<i64> {
+        (0..self.size)
+            .map(|row| Self::dot(self.row(row), vector, modulus))
+            .collect()
+    }
+
+    fn dot(a: &[i64], b: &[i64], modulus: i64) -> i64 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| x.rem_euclid(modulus) * y.rem_euclid(modulus) % modulus)
+            .sum::<i64>()
+            % modulus
+    }
+}
+
+#[cfg(test)]
+mod matrix_test {
+    use crate::error::CipherError;
+    use crate::modular::matrix::Matrix;
+
+    #[test]
+    fn new_checks_the_size() {
+        assert_eq!(Matrix::new(vec![1, 2, 3, 4]).unwrap().size(), 2);
+        assert_eq!(Matrix::new(vec![1, 2, 3]), Err(CipherError::NotSquareMatrix { length: 3 }));
+        assert_eq!(
+            Matrix::from_rows(vec![vec![1, 2], vec![3]]),
+            Err(CipherError::NotSquareMatrix { length: 3 })
+        );
+    }
+
+    #[test]
+    fn determinant_works() {
+        assert_eq!(Matrix::new(vec![5, 3, 9, 6]).unwrap().determinant(26), 3);
+        assert_eq!(
+            Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap().determinant(26),
+            25
+        );
+    }
+
+    #[test]
+    fn inverse_works() {
+        assert_eq!(
+            Matrix::new(vec![40, 61, 27, 21]).unwrap().inverse(26),
+            Ok(Matrix::new(vec![5, 9, 1, 12]).unwrap())
+        );
+        let key = Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap();
+        let inverse = key.inverse(26).unwrap();
+        assert_eq!(inverse, Matrix::new(vec![8, 5, 10, 21, 8, 21, 21, 12, 8]).unwrap());
+        assert_eq!(key.multiply(&inverse, 26), Matrix::identity(3));
+    }
+
+    #[test]
+    fn inverse_works_without_unit_pivots() {
+        // Neither 2 nor 13 has an inverse by 26, but the determinant does
+        let key = Matrix::new(vec![2, 13, 13, 2]).unwrap();
+        let inverse = key.inverse(26).unwrap();
+        assert_eq!(key.multiply(&inverse, 26), Matrix::identity(2));
+        assert_eq!(inverse, key.adjugate(26).multiply_by_number(23, 26));
+    }
+
+    #[test]
+    fn inverse_works_for_bigger_matrices() {
+        let key = Matrix::new(vec![1, 2, 3, 4, 0, 1, 4, 5, 5, 6, 0, 7, 8, 9, 10, 0]).unwrap();
+        let inverse = key.inverse(29).unwrap();
+        assert_eq!(key.multiply(&inverse, 29), Matrix::identity(4));
+        assert_eq!(inverse.multiply(&key, 29), Matrix::identity(4));
+    }
+
+    #[test]
+    fn inverse_fails_for_non_invertible_determinant() {
+        assert_eq!(
+            Matrix::new(vec![2, 4, 1, 3]).unwrap().inverse(26),
+            Err(CipherError::NonInvertibleKey { value: 2, modulus: 26 })
+        );
+    }
+
+    #[test]
+    fn multiply_vector_works() {
+        let key = Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap();
+        // ACT -> POH from the classic example
+        assert_eq!(key.multiply_vector(&[0, 2, 19], 26), vec![15, 14, 7]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Modular arithmetic the ciphers are built on.
+//! All results are in the range `0..modulus`, negative inputs are allowed.
+
+pub mod linear;
+pub mod matrix;
+
+pub use matrix::Matrix;
+
+pub fn gcd(a: i64, b: i64) -> i64 {
+    extended_gcd(a, b).0
+}
+
+/// Extended Euclidean algorithm, returns (gcd, x, y) where a*x + b*y = gcd
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The multiplicative inverse of a by the modulus, exists only when they are coprime
+pub fn inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (gcd, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if gcd == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+pub fn is_unit(a: i64, modulus: i64) -> bool {
+    gcd(a.rem_euclid(modulus), modulus) == 1
+}
+
+/// All numbers in 0..modulus which have inverses, these are the valid multiplicative keys
+pub fn units(modulus: i64) -> Vec<i64> {
+    (1..modulus).filter(|a| is_unit(*a, modulus)).collect()
+}
+
+/// Euler's totient function, the count of the numbers in 1..=n coprime with n
+pub fn totient(n: i64) -> i64 {
+    prime_factors(n)
+        .into_iter()
+        .fold(n, |result, (prime, _)| result / prime * (prime - 1))
+}
+
+/// The prime factors of n with their powers, in increasing order
+pub fn prime_factors(mut n: i64) -> Vec<(i64, u32)> {
+    let mut factors = vec![];
+    let mut prime = 2;
+    while prime * prime <= n {
+        let mut power = 0;
+        while n % prime == 0 {
+            n /= prime;
+            power += 1;
+        }
+        if power > 0 {
+            factors.push((prime, power));
+        }
+        prime += 1;
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Chinese remainder theorem, the number x which gives the given remainders by the given moduli.
+/// The moduli should be pairwise coprime, the result is by their product.
+pub fn crt(remainders: &[(i64, i64)]) -> Option<i64> {
+    remainders
+        .iter()
+        .try_fold((0, 1), |(x, modulus), (remainder, other_modulus)| {
+            // x + modulus * t = remainder (mod other_modulus)
+            let t = (remainder - x).rem_euclid(*other_modulus) * inverse(modulus, *other_modulus)? % other_modulus;
+            Some((x + modulus * t, modulus * other_modulus))
+        })
+        .map(|(x, _)| x)
+}
+
+/// base^exponent by the modulus, using square-and-multiply
+pub fn pow_mod(base: i64, mut exponent: u64, modulus: i64) -> i64 {
+    let modulus = modulus as i128;
+    let mut base = (base as i128).rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as i64
+}
+
+#[cfg(test)]
+mod modular_test {
+    use crate::modular::{crt, extended_gcd, gcd, inverse, pow_mod, prime_factors, totient, units};
+
+    #[test]
+    fn gcd_works() {
+        assert_eq!(gcd(26, 4), 2);
+        assert_eq!(gcd(-26, 13), 13);
+        assert_eq!(gcd(7, 26), 1);
+        assert_eq!(gcd(0, 5), 5);
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn inverse_works() {
+        assert_eq!(inverse(3, 26), Some(9));
+        assert_eq!(inverse(-3, 26), Some(17));
+        assert_eq!(inverse(239, 26), Some(21));
+        assert_eq!(inverse(13, 26), None);
+        assert_eq!(inverse(2, 33), Some(17));
+    }
+
+    #[test]
+    fn units_and_totient_agree() {
+        assert_eq!(units(26), vec![1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25]);
+        for n in 2..100 {
+            assert_eq!(units(n).len() as i64, totient(n));
+        }
+    }
+
+    #[test]
+    fn prime_factors_works() {
+        assert_eq!(prime_factors(26), vec![(2, 1), (13, 1)]);
+        assert_eq!(prime_factors(36), vec![(2, 2), (3, 2)]);
+        assert_eq!(prime_factors(29), vec![(29, 1)]);
+    }
+
+    #[test]
+    fn crt_works() {
+        assert_eq!(crt(&[(1, 2), (2, 13)]), Some(15));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some(23));
+        assert_eq!(crt(&[(1, 4), (1, 6)]), None);
+    }
+
+    #[test]
+    fn pow_mod_works() {
+        assert_eq!(pow_mod(4, 13, 497), 445);
+        assert_eq!(pow_mod(-2, 3, 26), 18);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+    }
+}
@@ -1,31 +1,88 @@
-use crate::monoalphabetic::additive::caesar::manipulations::decrypt;
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::monoalphabetic::additive::caesar::manipulations::CaesarCipher;
+use crate::scoring::frequencies::chi_squared;
+use crate::scoring::{rank_or_default, Scorer};
 
 pub struct CaesarHack {
-    encrypted_message: String
+    encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
 }
 
 impl CaesarHack {
     pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
         CaesarHack {
-            encrypted_message
+            encrypted_message,
+            alphabet,
+            scorer: None,
         }
     }
-    pub fn get_all_variants(self) -> Vec<(u8, String)> {
-        (1..26)
-            .map(|i| (i, decrypt(self.encrypted_message.clone(), i)))
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    pub fn get_all_variants(&self) -> Vec<(u32, String)> {
+        (1..self.alphabet.modulus())
+            .map(|i| {
+                let cipher = CaesarCipher::with_alphabet(i, self.alphabet.clone());
+                (i, cipher.decrypt(self.encrypted_message.clone()).unwrap())
+            })
             .collect()
     }
-    pub fn print_all_variants(self) {
+
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(u32, String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<(u32, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+
+    /// All shifts ranked by the chi-squared statistic of the decrypted message against the letter
+    /// frequencies of the alphabet, the most probable one first.
+    /// Unlike `get_all_variants` the shift 0 is included, empty if the alphabet has no frequencies
+    pub fn get_variants_by_frequencies(&self) -> Vec<(u32, String)> {
+        let message = self.alphabet.to_indices(&self.encrypted_message);
+        let modulus = self.alphabet.modulus();
+        let mut variants = (0..modulus)
+            .filter_map(|shift| {
+                let decrypted = message
+                    .iter()
+                    .map(|x| (x + modulus - shift) % modulus)
+                    .collect::<Vec<u32>>();
+                chi_squared(&decrypted, &self.alphabet).map(|score| (shift, decrypted, score))
+            })
+            .collect::<Vec<(u32, Vec<u32>, f64)>>();
+        variants.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+        variants
+            .into_iter()
+            .map(|(shift, decrypted, _)| (shift, self.alphabet.from_indices(&decrypted)))
+            .collect()
+    }
+
+    pub fn print_all_variants(&self) {
         self.get_all_variants()
             .iter()
             .for_each(|(i, t)| println!("{}: {}", i, t));
     }
 }
 
-
 #[cfg(test)]
 mod caesar_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
     use crate::monoalphabetic::additive::caesar::hacking::CaesarHack;
+    use crate::monoalphabetic::additive::caesar::manipulations::CaesarCipher;
+    use crate::scoring::DictionaryScorer;
 
     #[test]
     fn get_all_variant_contains_correct_value() {
@@ -35,32 +92,75 @@ mod caesar_hack_test {
     }
 
     #[test]
-    fn print_all_variant_works() {
-        CaesarHack::new("QUPCV OZGTM BAOMB IXQHH I".to_owned())
-            .print_all_variants();
+    fn get_all_variants_works_for_non_latin_alphabets() {
+        let russian = CaesarCipher::with_alphabet(3, Alphabet::russian()).encrypt("Съешь же ещё этих мягких французских булок".to_owned());
+        assert_eq!(russian, "ФЭЗЫЯ ЙЗЗЬИ АХЛШП ВЁНЛШ ЧУГРЩ ЦКФНЛ ШДЦОС Н".to_owned());
+        assert!(CaesarHack::with_alphabet(russian, Alphabet::russian())
+            .get_all_variants()
+            .contains(&(3, "СЪЕШЬЖЕЕЩЁЭТИХМЯГКИХФРАНЦУЗСКИХБУЛОК".to_owned())));
+
+        let armenian = CaesarCipher::with_alphabet(7, Alphabet::armenian()).encrypt("Բարև ձեզ".to_owned());
+        assert!(CaesarHack::with_alphabet(armenian, Alphabet::armenian())
+            .get_all_variants()
+            .contains(&(7, "ԲԱՐԵՒՁԵԶ".to_owned())));
+
+        let greek = CaesarCipher::with_alphabet(5, Alphabet::greek()).encrypt("Γειά σου κόσμε".to_owned());
+        assert!(CaesarHack::with_alphabet(greek, Alphabet::greek())
+            .get_all_variants()
+            .contains(&(5, "ΓΕΙΑΣΟΥΚΟΣΜΕ".to_owned())));
     }
 
     #[test]
-    fn task() {
-        // 6: THECARIWOULDMOSTLIKETOHAVEISAPORSCHE
-        // CaesarHack::new("ZNKIG XOCUA RJSUY ZROQK ZUNGB KOYGV UXYIN K".to_owned())
-        //     .print_all_variants();
+    fn get_all_variants_works_for_long_alphabets() {
+        let alphabet = Alphabet::new(&(0x4E00..0x4E00 + 300).filter_map(std::char::from_u32).collect::<String>()).unwrap();
+        let encrypted = CaesarCipher::with_alphabet(260, alphabet.clone()).encrypt("\u{4E00}\u{4E01}\u{4E02}".to_owned());
+        let variants = CaesarHack::with_alphabet(encrypted, alphabet).get_all_variants();
+        assert_eq!(variants.len(), 299);
+        assert!(variants.contains(&(260, "\u{4E00}\u{4E01}\u{4E02}".to_owned())));
+    }
 
-        // 4: WHENYOUGETTHEANSWERTOPROBLEMNINEPLEASECALLME
-        // CaesarHack::new("KVSBM CIUSH HVSOB GKSFH CDFCP ZSABW BSDZS OGSQO ZZAS".to_owned())
-        //     .print_all_variants();
+    #[test]
+    fn get_variants_by_frequencies_ranks_correct_shift_first() {
+        let encrypted = CaesarCipher::new(19).encrypt("When you get the answer to problem nine please call me".to_owned());
+        assert_eq!(
+            CaesarHack::new(encrypted).get_variants_by_frequencies()[0],
+            (19, "WHENYOUGETTHEANSWERTOPROBLEMNINEPLEASECALLME".to_owned())
+        );
+        let alphabet = Alphabet::new("ABC").unwrap();
+        assert!(CaesarHack::with_alphabet("ABC".to_owned(), alphabet).get_variants_by_frequencies().is_empty());
+    }
+
+    #[test]
+    fn best_finds_the_answer() {
+        let hack = CaesarHack::new("QUPCV OZGTM BAOMB IXQHH I".to_owned());
+        assert_eq!(hack.best().map(|x| (x.0, x.1)), Some((8, "IMHUNGRYLETSGETAPIZZA".to_owned())));
+        let ranked = hack.get_ranked_variants();
+        assert_eq!(ranked.len(), 25);
+        assert!(ranked.windows(2).all(|pair| pair[0].2 >= pair[1].2));
 
-        // 18: DONTFORGETTOBUYTHETICKETSTOTHECONCERT
-        // CaesarHack::new("VGFLX GJYWL LGTMQ LZWLA UCWLK LGLZW UGFUW JL".to_owned())
-        //     .print_all_variants();
+        let encrypted = "ZNKIG XOCUA RJSUY ZROQK ZUNGB KOYGV UXYIN K".to_owned();
+        let hack = CaesarHack::new(encrypted).with_scorer(DictionaryScorer::english());
+        assert_eq!(hack.best().unwrap().1, "THECARIWOULDMOSTLIKETOHAVEISAPORSCHE".to_owned());
+    }
 
-        // 24: WHENTHEDEFENSEBLITZESWEWILLTHROWASCREENPASS
-        // CaesarHack::new("UFCLR FCBCD CLQCZ JGRXC QUCUG JJRFP MUYQA PCCLN YQQ".to_owned())
-        //     .print_all_variants();
+    #[test]
+    fn print_all_variant_works() {
+        CaesarHack::new("QUPCV OZGTM BAOMB IXQHH I".to_owned())
+            .print_all_variants();
+    }
 
-        // 13: WEPLANTOVACATIONINBERMUDAINSTEADOFFLORIDATHISYEAR
-        // CaesarHack::new("JRCYN AGBIN PNGVB AVAOR EZHQN VAFGR NQBSS YBEVQ NGUVF LRNE".to_owned())
-        //     .print_all_variants();
+    #[test]
+    fn task() {
+        for (encrypted, shift, message) in [
+            ("ZNKIG XOCUA RJSUY ZROQK ZUNGB KOYGV UXYIN K", 6, "THECARIWOULDMOSTLIKETOHAVEISAPORSCHE"),
+            ("KVSBM CIUSH HVSOB GKSFH CDFCP ZSABW BSDZS OGSQO ZZAS", 14, "WHENYOUGETTHEANSWERTOPROBLEMNINEPLEASECALLME"),
+            ("VGFLX GJYWL LGTMQ LZWLA UCWLK LGLZW UGFUW JL", 18, "DONTFORGETTOBUYTHETICKETSTOTHECONCERT"),
+            ("UFCLR FCBCD CLQCZ JGRXC QUCUG JJRFP MUYQA PCCLN YQQ", 24, "WHENTHEDEFENSEBLITZESWEWILLTHROWASCREENPASS"),
+            ("JRCYN AGBIN PNGVB AVAOR EZHQN VAFGR NQBSS YBEVQ NGUVF LRNE", 13, "WEPLANTOVACATIONINBERMUDAINSTEADOFFLORIDATHISYEAR"),
+        ] {
+            let best = CaesarHack::new(encrypted.to_owned()).best().unwrap();
+            assert_eq!((best.0, best.1), (shift, message.to_owned()));
+        }
     }
 }
 
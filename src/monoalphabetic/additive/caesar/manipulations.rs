@@ -1,31 +1,78 @@
-fn manipulate(message: String, shift: u8) -> String {
-    (&message)
-        .chars()
-        .filter(|x| x.is_ascii() && x.is_alphabetic())
-        .map(|x| {
-            (((x.to_uppercase().next().unwrap() as u8) - 'A' as u8 + shift) % 26 + 'A' as u8)
-                as char
-        })
-        .collect()
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+
+pub struct CaesarCipher {
+    pub shift: u32,
+    alphabet: Alphabet,
+}
+
+impl CaesarCipher {
+    pub fn new(shift: u32) -> Self {
+        Self::with_alphabet(shift, Alphabet::english())
+    }
+
+    pub fn with_alphabet(shift: u32, alphabet: Alphabet) -> Self {
+        CaesarCipher {
+            shift: shift % alphabet.modulus(),
+            alphabet,
+        }
+    }
+}
+
+impl Cipher for CaesarCipher {
+    type Key = u32;
+
+    fn name(&self) -> &'static str {
+        "Caesar"
+    }
+
+    fn key(&self) -> u32 {
+        self.shift
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn preserves_positions(&self) -> bool {
+        true
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&manipulate(message, self.shift, &self.alphabet))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let modulus = self.alphabet.modulus();
+        Ok(manipulate(encrypted_message, modulus - self.shift, &self.alphabet))
+    }
+}
+
+fn manipulate(message: String, shift: u32, alphabet: &Alphabet) -> String {
+    alphabet.from_indices(
+        &alphabet
+            .to_indices(&message)
+            .into_iter()
+            .map(|x| (x + shift) % alphabet.modulus())
+            .collect::<Vec<u32>>(),
+    )
 }
 
-pub fn encrypt(message: String, key: u8) -> String {
-    manipulate(message, key)
-        .chars()
-        .collect::<Vec<char>>()
-        .chunks(5)
-        .map(|ch| ch.iter().collect::<String>())
-        .collect::<Vec<String>>()
-        .join(" ")
+pub fn encrypt(message: String, key: u32) -> String {
+    CaesarCipher::new(key).encrypt(message)
 }
 
-pub fn decrypt(message: String, key: u8) -> String {
-    manipulate(message, 26 - key)
+pub fn decrypt(message: String, key: u32) -> String {
+    CaesarCipher::new(key).decrypt(message).unwrap()
 }
 
 #[cfg(test)]
 mod caesar_tests {
-    use crate::monoalphabetic::additive::caesar::manipulations::{decrypt, encrypt};
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::monoalphabetic::additive::caesar::manipulations::{decrypt, encrypt, CaesarCipher};
 
     #[test]
     fn encrypt_works_and_chunks_are_created() {
@@ -58,4 +105,20 @@ mod caesar_tests {
             "SOMEMESSAGE".to_owned()
         );
     }
+
+    #[test]
+    fn caesar_cipher_matches_free_functions() {
+        let cipher = CaesarCipher::new(29);
+        assert_eq!(cipher.key(), 3);
+        assert_eq!(cipher.encrypt("some message".to_owned()), "VRPHP HVVDJ H".to_owned());
+        assert_eq!(cipher.decrypt("VRPHP HVVDJ H".to_owned()), Ok("SOMEMESSAGE".to_owned()));
+    }
+
+    #[test]
+    fn caesar_cipher_works_with_custom_alphabet() {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap();
+        let cipher = CaesarCipher::with_alphabet(5, alphabet);
+        assert_eq!(cipher.encrypt("Room 42".to_owned()), "WTTR9 7".to_owned());
+        assert_eq!(cipher.decrypt("WTTR9 7".to_owned()), Ok("ROOM42".to_owned()));
+    }
 }
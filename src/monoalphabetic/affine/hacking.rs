@@ -1,15 +1,35 @@
+use crate::alphabet::Alphabet;
+use crate::analysis::FrequencyReport;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::modular::linear;
 use crate::monoalphabetic::affine::manipulations::AffineCipher;
-use crate::constants::functions::alphabet::{letter_to_index};
-use std::collections::{HashMap, HashSet};
-
+use crate::scoring::{rank_or_default, Scorer};
 
 pub struct AffineCipherHack {
     encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
 }
 
 impl AffineCipherHack {
     pub fn new(encrypted_message: String) -> Self {
-        AffineCipherHack { encrypted_message: encrypted_message.chars().filter(|c|c.is_alphabetic()).collect() }
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    /// The alphabet should have the letter frequencies of its language for the frequency analysis
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        AffineCipherHack {
+            encrypted_message: encrypted_message.chars().filter(|c| alphabet.contains(*c)).collect(),
+            alphabet,
+            scorer: None,
+        }
+    }
+
+    /// The scorer used to rank the options, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
     }
 
     pub fn get_all_options_based_on_common_letters(&self) -> Vec<(u32, u32, String)> {
@@ -17,104 +37,276 @@ impl AffineCipherHack {
     }
 
     pub fn get_all_options_based_on_common_letters_with_depth(&self, depth: usize) -> Vec<(u32, u32, String)> {
+        // The most common letter of the language, E for English
+        let guess = match self.alphabet.most_frequent_symbols().first() {
+            Some(letter) => self.alphabet.index_of(*letter).unwrap(),
+            None => return vec![],
+        };
         self.find_most_common_letter(depth)
             .iter()
-            // this can be changed to check the T letter too!
-            .map(|c| self.try_with_guess(letter_to_index(*c) as u32, letter_to_index('e') as u32))
-            .flatten()
+            // match_frequencies checks the other common letters too
+            .flat_map(|c| self.try_with_guess(self.alphabet.index_of(*c).unwrap(), guess))
+            .collect()
+    }
+
+    /// The options based on the common letters with their scores, the best one first
+    pub fn get_ranked_options(&self) -> Vec<(u32, u32, String, f64)> {
+        self.rank_options(self.get_all_options_based_on_common_letters())
+    }
+
+    pub fn best(&self) -> Option<(u32, u32, String, f64)> {
+        self.get_ranked_options().into_iter().next()
+    }
+
+    /// Ranks any options, like the ones from `try_with_guess`
+    pub fn rank_options(&self, options: Vec<(u32, u32, String)>) -> Vec<(u32, u32, String, f64)> {
+        let options = options.into_iter().map(|(a, b, message)| ((a, b), message)).collect();
+        rank_or_default(options, self.scorer.as_deref(), &self.alphabet)
+            .into_iter()
+            .map(|((a, b), message, score)| (a, b, message, score))
             .collect()
     }
 
+    /// Every valid key (12 * 26 for English) with the decrypted message and its score, the best one first.
+    /// Unlike the frequency guesses this works for short messages too
+    pub fn brute_force(&self) -> Vec<(u32, u32, String, f64)> {
+        let modulus = self.alphabet.modulus();
+        let options = (1..modulus)
+            .flat_map(|a| (0..modulus).map(move |b| (a, b)))
+            // Only the keys which have inverses can be used
+            .filter_map(|(a, b)| AffineCipher::with_alphabet(a, b, self.alphabet.clone()).ok())
+            .map(|cipher| (cipher.a, cipher.b, cipher.decrypt(self.encrypted_message.clone()).unwrap()))
+            .collect();
+        self.rank_options(options)
+    }
+
+    /**
+    When you know the encryptions of two letters, the key can be found directly:
+    a*p1 + b = c1 and a*p2 + b = c2 mod m.
+    first and second are (plaintext letter, ciphertext letter) pairs.
+    Several keys are returned when the difference of the plaintext letters has no inverse
+    */
+    pub fn solve_with_known_letters(
+        &self,
+        first: (char, char),
+        second: (char, char),
+    ) -> Result<Vec<(u32, u32, String)>, CipherError> {
+        let index = |c: char| self.alphabet.index_of(c).map(|x| x as i64).ok_or(CipherError::SymbolNotInAlphabet(c));
+        let coefficients = vec![vec![index(first.0)?, 1], vec![index(second.0)?, 1]];
+        let values = [index(first.1)?, index(second.1)?];
+        Ok(linear::solve(&coefficients, &values, self.alphabet.modulus() as i64)
+            .into_iter()
+            .filter_map(|key| AffineCipher::with_alphabet(key[0] as u32, key[1] as u32, self.alphabet.clone()).ok())
+            .map(|cipher| (cipher.a, cipher.b, cipher.decrypt(self.encrypted_message.clone()).unwrap()))
+            .collect())
+    }
+
+    /**
+    Frequency analysis with several letters: every pair of the `depth` most common letters of the
+    message is matched with every pair of the `depth` most common letters of the language (E, T, A, O...),
+    the key is solved for every such assumption.
+    Returns the distinct keys with the number of the common letters of the message they decrypt to
+    the common letters of the language, the keys satisfying the most assumptions first
+    */
+    pub fn match_frequencies(&self, depth: usize) -> Vec<(u32, u32, usize, String)> {
+        let encrypted = self.letters_by_frequency().into_iter().take(depth).collect::<Vec<char>>();
+        let language = self.alphabet.most_frequent_symbols().into_iter().take(depth).collect::<Vec<char>>();
+        let mut keys: Vec<(u32, u32, String)> = vec![];
+        for (i, c1) in encrypted.iter().enumerate() {
+            for c2 in &encrypted[i + 1..] {
+                for p1 in &language {
+                    for p2 in language.iter().filter(|p2| *p2 != p1) {
+                        for key in self.solve_with_known_letters((*p1, *c1), (*p2, *c2)).unwrap() {
+                            if !keys.iter().any(|k| (k.0, k.1) == (key.0, key.1)) {
+                                keys.push(key);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let mut results = keys
+            .into_iter()
+            .map(|(a, b, message)| {
+                let cipher = AffineCipher::with_alphabet(a, b, self.alphabet.clone()).unwrap();
+                let decrypted = cipher.decrypt(encrypted.iter().collect()).unwrap();
+                let matches = decrypted.chars().filter(|c| language.contains(c)).count();
+                (a, b, matches, message)
+            })
+            .collect::<Vec<(u32, u32, usize, String)>>();
+        results.sort_by(|x, y| y.2.cmp(&x.2).then((x.0, x.1).cmp(&(y.0, y.1))));
+        results
+    }
+
+    /// All letters of the message from the most common one, the letters with the same count keep
+    /// the order of the alphabet
+    fn letters_by_frequency(&self) -> Vec<char> {
+        self.frequency_report()
+            .most_common_letters()
+            .into_iter()
+            .map(|x| x.0)
+            .collect()
+    }
+
+    /// The letters with the `depth` highest counts of the message (all of them for the same count).
+    /// The letters are counted through the alphabet, so they're written as in the alphabet whatever
+    /// the case of the message: "Hello there" gives 'E', not 'e' like before the alphabets were added.
     fn find_most_common_letter(&self, depth: usize) -> Vec<char> {
-        let mut map: HashMap<char, u32> = HashMap::new();
-        self.encrypted_message
-            .chars()
-            .for_each(|c| if map.contains_key(&c) {
-                map.insert(c, map.get(&c).unwrap() + 1);
-            } else {
-                map.insert(c, 1);
-            });
-        let mut sorted_map = map.into_iter()
-            .collect::<Vec<(char, u32)>>();
-        sorted_map.sort_by(|a, b| a.1.cmp(&b.1));
-        let mut best_ones = HashSet::new();
-        sorted_map
-            .iter()
-            .rev()
-            .for_each(|x| if best_ones.len() < depth {
-                best_ones.insert(x.1);
-            });
-        sorted_map.iter()
-            .filter(|x| best_ones.contains(&x.1))
+        let letters = self.frequency_report().most_common_letters();
+        let mut counts = letters.iter().map(|x| x.1).collect::<Vec<usize>>();
+        counts.dedup();
+        letters
+            .into_iter()
+            .filter(|x| counts.iter().take(depth).any(|count| *count == x.1))
             .map(|x| x.0)
             .collect()
     }
 
+    /// Letter statistics of the encrypted message
+    pub fn frequency_report(&self) -> FrequencyReport {
+        FrequencyReport::with_alphabet(&self.encrypted_message, self.alphabet.clone())
+    }
+
     /**
     When you have a guess that can be used to decipher the message, give it here
     encrypted - the letter alphabetic index
     guess - the guessed value alphabetic index
     */
     pub fn try_with_guess(&self, encrypted: u32, guess: u32) -> Vec<(u32, u32, String)> {
-        (1..26)
-            .filter(|a| a % 2 != 0 && *a != 13)
-            .map(|a| (a, Self::get_b(encrypted, guess, a)))
-            .map(|(a, b)| (a, b, self.check_a_and_b(a, b)))
+        let modulus = self.alphabet.modulus();
+        (1..modulus)
+            // Only the keys which have inverses can be used
+            .filter_map(|a| AffineCipher::with_alphabet(a, Self::get_b(encrypted, guess, a, modulus), self.alphabet.clone()).ok())
+            .map(|cipher| (cipher.a, cipher.b, cipher.decrypt(self.encrypted_message.clone()).unwrap()))
             .collect()
     }
 
-    fn check_a_and_b(&self, a: u32, b: u32) -> String {
-        AffineCipher::new(a, b)
-            .unwrap()
-            .decrypt(self.encrypted_message.clone())
-    }
-
-    fn get_b(encrypted: u32, guess: u32, a: u32) -> u32 {
-        (encrypted + 26 - (a * guess) % 26) % 26
+    fn get_b(encrypted: u32, guess: u32, a: u32, modulus: u32) -> u32 {
+        (encrypted + modulus - (a * guess) % modulus) % modulus
     }
 }
 
 #[cfg(test)]
 mod affine_cipher_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
     use crate::monoalphabetic::affine::hacking::AffineCipherHack;
-    use crate::constants::functions::alphabet::letter_to_index;
+    use crate::monoalphabetic::affine::manipulations::AffineCipher;
 
     #[test]
-    fn test() {
-        /*
-        QPIFY EPKLX YYPRX XYSXX UXWSV IYRTS XIHPF YVNXH PFKYK ZWYPY SXOPP
-        7 15 DONUTFORGETTOSEETHEELEPHANTSWHENYOUTAKEYOURTRIPTOTHEZOO
-
-        AEKHF FYOKK FJKHK EOFUX OEUXO QYNAK LWFAT ATHFK HRYMA FHKUX ZMUOQ WCJUO FJKKU XZAKX YHKO
-        17 4 IWENTTOSEETHENEWSTARSWARSMOVIEBUTIDIDNTENJOYITNEARLYASMUCHASTHEEARLIERONES
-
-        FVMHA FMCFL MZCYQ NQPFV MCGVQ QHNMO CTATM ZGQNF AYNMJ
-        AHAZS MMZZQ ZFVAF GAICM JYFFQ DMZMT ZYNFM JGQCF YNSFV
-        MGHID AHQFQ PKQNM U
-        3 24 THELATESTVERSIONOFTHESCHOOLNEWSPAPERCONTAINEDALARGEERRORTHATCAUSEDITTOBEREPRINTEDCOSTINGTHECLUBALOTOFMONEY
-
-        NTYNC NSOGN XGNGQ NSNSN UIGEX GFNXG NGSMX GTUQZ TGQGF
-        NQCNX SNXGM SFNSO GWKGQ NUCFQ
-        23 22 TRYTOTAKETHETESTATATIMEWHENTHETEACHERISPRESENTSOTHATHECANTAKEQUESTIONS
-        */
-        for message in
-            AffineCipherHack::new("NTYNC NSOGN XGNGQ NSNSN UIGEX GFNXG NGSMX GTUQZ TGQGF
-NQCNX SNXGM SFNSO GWKGQ NUCFQ".to_owned())
-                .get_all_options_based_on_common_letters_with_depth(1) {
-            println!("{} {} {}", message.0, message.1, message.2);
+    fn common_letters_find_the_known_keys() {
+        let known = [
+            (
+                "QPIFY EPKLX YYPRX XYSXX UXWSV IYRTS XIHPF YVNXH PFKYK ZWYPY SXOPP",
+                7,
+                15,
+                "DONUTFORGETTOSEETHEELEPHANTSWHENYOUTAKEYOURTRIPTOTHEZOO",
+            ),
+            (
+                "AEKHF FYOKK FJKHK EOFUX OEUXO QYNAK LWFAT ATHFK HRYMA FHKUX ZMUOQ WCJUO FJKKU XZAKX YHKO",
+                17,
+                4,
+                "IWENTTOSEETHENEWSTARSWARSMOVIEBUTIDIDNTENJOYITNEARLYASMUCHASTHEEARLIERONES",
+            ),
+            (
+                "FVMHA FMCFL MZCYQ NQPFV MCGVQ QHNMO CTATM ZGQNF AYNMJ AHAZS MMZZQ ZFVAF GAICM JYFFQ DMZMT ZYNFM \
+                 JGQCF YNSFV MGHID AHQFQ PKQNM U",
+                3,
+                24,
+                "THELATESTVERSIONOFTHESCHOOLNEWSPAPERCONTAINEDALARGEERRORTHATCAUSEDITTOBEREPRINTEDCOSTINGTHECLUBALOTOFMONEY",
+            ),
+            (
+                "NTYNC NSOGN XGNGQ NSNSN UIGEX GFNXG NGSMX GTUQZ TGQGF\nNQCNX SNXGM SFNSO GWKGQ NUCFQ",
+                23,
+                22,
+                "TRYTOTAKETHETESTATATIMEWHENTHETEACHERISPRESENTSOTHATHECANTAKEQUESTIONS",
+            ),
+        ];
+        // T is more common than E in the last message, so the second most common letter is tried too
+        for (encrypted, a, b, message) in known.iter() {
+            let hack = AffineCipherHack::new(encrypted.to_string());
+            let options = hack.get_all_options_based_on_common_letters_with_depth(2);
+            assert!(options.contains(&(*a, *b, message.to_string())), "{}", encrypted);
+            let ranked = hack.rank_options(options);
+            assert_eq!((ranked[0].0, ranked[0].1), (*a, *b));
         }
     }
 
     #[test]
     fn try_with_guess_contains_correct_option() {
         assert!(AffineCipherHack::new("RPIID XHIGGG MPOOH UIQVA GONIV QDXYI PQNII AEPRY IIWGOT T".to_owned())
-            .try_with_guess(letter_to_index('I') as u32, letter_to_index('e') as u32)
+            .try_with_guess(Alphabet::english().index_of('I').unwrap(), Alphabet::english().index_of('e').unwrap())
             .contains(&(11, 6, "THEENDLESSSCHOOLYEARISOVERANDWEHAVEEIGHTWEEKSOFF".to_owned())))
     }
 
+    #[test]
+    fn best_finds_the_answer() {
+        let hack = AffineCipherHack::new("QPIFY EPKLX YYPRX XYSXX UXWSV IYRTS XIHPF YVNXH PFKYK ZWYPY SXOPP".to_owned());
+        assert_eq!(
+            hack.best().map(|x| (x.0, x.1, x.2)),
+            Some((7, 15, "DONUTFORGETTOSEETHEELEPHANTSWHENYOUTAKEYOURTRIPTOTHEZOO".to_owned()))
+        );
+        let ranked = hack.rank_options(hack.try_with_guess(24, 5));
+        assert!(ranked.windows(2).all(|pair| pair[0].3 >= pair[1].3));
+    }
+
+    #[test]
+    fn brute_force_works_for_short_messages() {
+        let encrypted = AffineCipher::new(5, 8).unwrap().encrypt("Meet me at the park at noon".to_owned());
+        let results = AffineCipherHack::new(encrypted).brute_force();
+        assert_eq!(results.len(), 312);
+        assert_eq!((results[0].0, results[0].1, results[0].2.as_str()), (5, 8, "MEETMEATTHEPARKATNOON"));
+    }
+
+    #[test]
+    fn solve_with_known_letters_works() {
+        let encrypted = AffineCipher::new(7, 15).unwrap().encrypt("Don't forget to see the elephants".to_owned());
+        let hack = AffineCipherHack::new(encrypted);
+        // D -> Q and O -> P
+        assert_eq!(
+            hack.solve_with_known_letters(('d', 'Q'), ('o', 'P')),
+            Ok(vec![(7, 15, "DONTFORGETTOSEETHEELEPHANTS".to_owned())])
+        );
+        // E - A = 4 has no inverse by 26, so both keys with a = 7 and a = 20 fit, but only one can be used
+        let options = hack.solve_with_known_letters(('a', 'V'), ('e', 'X')).unwrap();
+        assert_eq!(options.iter().map(|x| (x.0, x.1)).collect::<Vec<(u32, u32)>>(), vec![(7, 15)]);
+        assert_eq!(hack.solve_with_known_letters(('d', '?'), ('o', 'P')), Err(CipherError::SymbolNotInAlphabet('?')));
+    }
+
+    #[test]
+    fn match_frequencies_finds_key_satisfying_most_assumptions() {
+        let encrypted = AffineCipher::new(17, 4)
+            .unwrap()
+            .encrypt("I went to see the new Star Wars movie but I didnt enjoy it nearly as much as the earlier ones".to_owned());
+        let results = AffineCipherHack::new(encrypted).match_frequencies(6);
+        assert!(results.windows(2).all(|pair| pair[0].2 >= pair[1].2));
+        assert_eq!((results[0].0, results[0].1, results[0].2), (17, 4, 5));
+        assert!(results[1].2 < 5);
+        let keys = results.iter().map(|x| (x.0, x.1)).collect::<std::collections::HashSet<(u32, u32)>>();
+        assert_eq!(keys.len(), results.len());
+    }
+
     #[test]
     fn find_most_common_letters_works() {
         assert_eq!(AffineCipherHack::new("Hello there".to_owned())
-                       .find_most_common_letter(1), vec!['e']);
+                       .find_most_common_letter(1), vec!['E']);
+    }
+
+    #[test]
+    fn works_for_russian() {
+        let message = "Москва - столица России, один из крупнейших городов мира. Она расположена на реке Москве.";
+        let encrypted = AffineCipher::with_alphabet(7, 4, Alphabet::russian()).unwrap().encrypt(message.to_owned());
+        assert!(AffineCipherHack::with_alphabet(encrypted, Alphabet::russian())
+            .get_all_options_based_on_common_letters()
+            .contains(&(7, 4, Alphabet::russian().normalize(message))));
+    }
+
+    #[test]
+    fn alphabet_without_frequencies_gives_no_options() {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap();
+        assert!(AffineCipherHack::with_alphabet("PHONY GARUH".to_owned(), alphabet)
+            .get_all_options_based_on_common_letters()
+            .is_empty());
     }
 }
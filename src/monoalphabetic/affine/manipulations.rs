@@ -1,70 +1,91 @@
-use crate::constants::functions::alphabet::{index_to_letter, letter_to_index};
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::modular;
 
 pub struct AffineCipher {
-    // a*p+b = C mod 26 - encrypting
+    // a*p+b = C mod m - encrypting
     pub a: u32,
     pub b: u32,
-    // c*C + d = p mod 26 - decrypting
+    // c*C + d = p mod m - decrypting
     pub c: u32,
     pub d: u32,
+    alphabet: Alphabet,
 }
 
 impl AffineCipher {
-    pub fn new(a: u32, b: u32) -> Result<Self, String> {
-        if !Self::verify_key(a) {
-            return Err("Invalid key!".to_owned());
-        }
-        let (c, d) = Self::get_decryption_key(a, b).unwrap();
-        Ok(AffineCipher { a, b, c, d })
+    pub fn new(a: u32, b: u32) -> Result<Self, CipherError> {
+        Self::with_alphabet(a, b, Alphabet::english())
     }
 
-    fn verify_key(key: u32) -> bool {
-        // For these keys we don't have inverses and won't be able to decrypt the message
-        if key % 2 == 0 || key % 13 == 0 {
-            false
-        } else {
-            true
-        }
+    pub fn with_alphabet(a: u32, b: u32, alphabet: Alphabet) -> Result<Self, CipherError> {
+        // For keys without inverses we won't be able to decrypt the message
+        let (c, d) = Self::get_decryption_key(a, b, alphabet.modulus()).ok_or(CipherError::NonInvertibleKey {
+            value: a as i64,
+            modulus: alphabet.modulus(),
+        })?;
+        Ok(AffineCipher { a, b, c, d, alphabet })
     }
 
-    fn get_decryption_key(a: u32, b: u32) -> Option<(u32, u32)> {
-        let inverse = Self::get_multiplicative_inverse(a)?;
+    fn get_decryption_key(a: u32, b: u32, modulus: u32) -> Option<(u32, u32)> {
+        let inverse = Self::get_multiplicative_inverse(a, modulus)?;
         Some((
             inverse,
-            ((((-((b * inverse) as i32)) % 26) + 26) % 26) as u32,
+            (-((b % modulus * inverse) as i64)).rem_euclid(modulus as i64) as u32,
         ))
     }
 
-    fn get_multiplicative_inverse(a: u32) -> Option<u32> {
-        (1..26u32).find(|x| x * a % 26 == 1)
+    fn get_multiplicative_inverse(a: u32, modulus: u32) -> Option<u32> {
+        modular::inverse(a as i64, modulus as i64).map(|x| x as u32)
     }
 
-    pub fn encrypt(&self, message: String) -> String {
-        message
-            .to_uppercase()
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .map(|c| index_to_letter(((letter_to_index(c) as u32 * self.a + self.b) % 26) as u8))
-            .collect::<Vec<char>>()
-            .chunks(5)
-            .map(|x| x.iter().collect::<String>())
-            .collect::<Vec<String>>()
-            .join(" ")
+    fn transform(&self, message: &str, factor: u32, shift: u32) -> String {
+        let modulus = self.alphabet.modulus();
+        self.alphabet.from_indices(
+            &self
+                .alphabet
+                .to_indices(message)
+                .into_iter()
+                .map(|x| (x * (factor % modulus) + shift % modulus) % modulus)
+                .collect::<Vec<u32>>(),
+        )
+    }
+}
+
+impl Cipher for AffineCipher {
+    type Key = (u32, u32);
+
+    fn name(&self) -> &'static str {
+        "Affine"
+    }
+
+    fn key(&self) -> (u32, u32) {
+        (self.a, self.b)
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn preserves_positions(&self) -> bool {
+        true
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&self.transform(&message, self.a, self.b))
     }
 
-    pub fn decrypt(&self, encrypted_message: String) -> String {
-        encrypted_message
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .map(|c| {
-                index_to_letter(((letter_to_index(c) as u32 * self.c + self.d) % 26) as u8)
-            })
-            .collect()
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        Ok(self.transform(&encrypted_message, self.c, self.d))
     }
 }
 
 #[cfg(test)]
 mod affine_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
     use crate::monoalphabetic::affine::manipulations::AffineCipher;
 
     #[test]
@@ -76,6 +97,22 @@ mod affine_test {
     #[test]
     fn decrypt_works() {
         let cipher = AffineCipher::new(239, 152).unwrap();
-        assert_eq!(cipher.decrypt("PHONY GARUH".to_owned()), "DRINKWATER".to_owned());
+        assert_eq!(cipher.decrypt("PHONY GARUH".to_owned()), Ok("DRINKWATER".to_owned()));
+    }
+
+    #[test]
+    fn fails_with_non_invertible_key() {
+        assert_eq!(
+            AffineCipher::new(4, 3).err(),
+            Some(CipherError::NonInvertibleKey { value: 4, modulus: 26 })
+        );
+    }
+
+    #[test]
+    fn works_with_custom_alphabet() {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZÆØÅ").unwrap();
+        let cipher = AffineCipher::with_alphabet(3, 7, alphabet).unwrap();
+        let encrypted = cipher.encrypt("Smørrebrød på bordet".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("SMØRREBRØDPÅBORDET".to_owned()));
     }
 }
@@ -1,3 +1,4 @@
 pub mod affine;
 pub mod multiplicative;
 pub mod additive;
+pub mod substitution;
@@ -1,28 +1,50 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
 use crate::monoalphabetic::multiplicative::manipulations::MultiplicativeCipher;
+use crate::scoring::{rank_or_default, Scorer};
 
 pub struct MultiplicativeCipherHack {
     encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
 }
 
 impl MultiplicativeCipherHack {
     pub fn new(encrypted_message: String) -> Self {
-        MultiplicativeCipherHack { encrypted_message }
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        MultiplicativeCipherHack {
+            encrypted_message,
+            alphabet,
+            scorer: None,
+        }
+    }
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
     }
 
     pub fn get_all_variants(&self) -> Vec<(u32, String)> {
-        (1..26)
-            .filter(|k| k % 2 != 0 && k % 13 != 0)
-            .map(|possible_key| {
-                (
-                    possible_key,
-                    MultiplicativeCipher::new(possible_key)
-                        .unwrap()
-                        .decrypt(self.encrypted_message.clone()),
-                )
-            })
+        (1..self.alphabet.modulus())
+            // Only the keys which have inverses can be used
+            .filter_map(|possible_key| MultiplicativeCipher::with_alphabet(possible_key, self.alphabet.clone()).ok())
+            .map(|cipher| (cipher.key(), cipher.decrypt(self.encrypted_message.clone()).unwrap()))
             .collect()
     }
 
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(u32, String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<(u32, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+
     pub fn print_all_variants(&self) {
         self.get_all_variants()
             .iter()
@@ -32,7 +54,10 @@ impl MultiplicativeCipherHack {
 
 #[cfg(test)]
 mod multiplicative_cipher_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
     use crate::monoalphabetic::multiplicative::hacking::MultiplicativeCipherHack;
+    use crate::monoalphabetic::multiplicative::manipulations::MultiplicativeCipher;
 
     #[test]
     fn get_all_variants_contains_correct_option() {
@@ -43,6 +68,28 @@ mod multiplicative_cipher_hack_test {
         .contains(&(17u32, "CANYOUGIVEMEAAARIDEHOMEAFTERSCHOOL".to_owned())))
     }
 
+    #[test]
+    fn get_all_variants_works_for_russian() {
+        let encrypted = MultiplicativeCipher::with_alphabet(5, Alphabet::russian())
+            .unwrap()
+            .encrypt("Привет, мир".to_owned());
+        let variants = MultiplicativeCipherHack::with_alphabet(encrypted, Alphabet::russian()).get_all_variants();
+        assert_eq!(variants.len(), 20);
+        assert!(variants.contains(&(5, "ПРИВЕТМИР".to_owned())));
+    }
+
+    #[test]
+    fn best_finds_the_answer() {
+        let hack = MultiplicativeCipherHack::new("CIMOG FSXTS SIKDS OYCDD YCVRQ MEFSX TIVOC HNECV XO".to_owned());
+        assert_eq!(
+            hack.best().map(|x| (x.0, x.1)),
+            Some((9, "IAMSURETHEEAGLESWILLWINBYMORETHANSIXPOINTS".to_owned()))
+        );
+        let variants = MultiplicativeCipherHack::with_alphabet("ПРИВЕТ".to_owned(), Alphabet::russian())
+            .get_ranked_variants();
+        assert_eq!(variants.len(), 20);
+    }
+
     /*
     CIMOG FSXTS SIKDS OYCDD YCVRQ MEFSX TIVOC HNECV XO
     M 9: IAMSURETHEEAGLESWILLWINBYMORETHANSIXPOINTS
@@ -1,57 +1,84 @@
-use crate::constants::functions::alphabet::{index_to_letter, letter_to_index};
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::modular;
 
 pub struct MultiplicativeCipher {
     pub factor: u32,
-    pub decryption_key: u32
+    pub decryption_key: u32,
+    alphabet: Alphabet,
 }
 
 impl MultiplicativeCipher {
-    pub fn new(factor: u32) -> Result<Self, String> {
-        if !Self::verify_key(factor) {
-            return Err("Invalid key!".to_owned());
-        }
-        let decryption_key = Self::get_decryption_key(factor).unwrap();
-        Ok(MultiplicativeCipher {factor, decryption_key})
+    pub fn new(factor: u32) -> Result<Self, CipherError> {
+        Self::with_alphabet(factor, Alphabet::english())
     }
 
-    fn verify_key(key: u32) -> bool {
-        // For these keys we don't have inverses and won't be able to decrypt the message
-        if key % 2 == 0 || key % 13 == 0 {
-            false
-        } else {
-            true
-        }
+    pub fn with_alphabet(factor: u32, alphabet: Alphabet) -> Result<Self, CipherError> {
+        // For keys without inverses we won't be able to decrypt the message
+        let decryption_key =
+            Self::get_decryption_key(factor, alphabet.modulus()).ok_or(CipherError::NonInvertibleKey {
+                value: factor as i64,
+                modulus: alphabet.modulus(),
+            })?;
+        Ok(MultiplicativeCipher {
+            factor,
+            decryption_key,
+            alphabet,
+        })
     }
 
-    fn get_decryption_key(key: u32) -> Option<u32> {
-        (1..26u32).find(|x| x * key % 26 == 1)
+    fn get_decryption_key(key: u32, modulus: u32) -> Option<u32> {
+        modular::inverse(key as i64, modulus as i64).map(|x| x as u32)
     }
 
-    pub fn encrypt(&self, message: String) -> String {
-        message
-            .to_uppercase()
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .map(|c| index_to_letter(((letter_to_index(c) as u32 * self.factor) % 26) as u8))
-            .collect::<Vec<char>>()
-            .chunks(5)
-            .map(|x| x.iter().collect::<String>())
-            .collect::<Vec<String>>()
-            .join(" ")
+    fn multiply(&self, message: &str, factor: u32) -> String {
+        let modulus = self.alphabet.modulus();
+        self.alphabet.from_indices(
+            &self
+                .alphabet
+                .to_indices(message)
+                .into_iter()
+                .map(|x| x * (factor % modulus) % modulus)
+                .collect::<Vec<u32>>(),
+        )
     }
+}
+
+impl Cipher for MultiplicativeCipher {
+    type Key = u32;
 
-    pub fn decrypt(&self, encrypted_message: String) -> String {
-        encrypted_message
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .map(|c| index_to_letter(((letter_to_index(c) as u32 * self.decryption_key) % 26) as u8))
-            .collect()
+    fn name(&self) -> &'static str {
+        "Multiplicative"
+    }
+
+    fn key(&self) -> u32 {
+        self.factor
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn preserves_positions(&self) -> bool {
+        true
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&self.multiply(&message, self.factor))
     }
-}
 
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        Ok(self.multiply(&encrypted_message, self.decryption_key))
+    }
+}
 
 #[cfg(test)]
 mod multiplicative_tests {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
     use crate::monoalphabetic::multiplicative::manipulations::MultiplicativeCipher;
 
     #[test]
@@ -60,6 +87,10 @@ mod multiplicative_tests {
         assert!(MultiplicativeCipher::new(4).is_err());
         assert!(MultiplicativeCipher::new(13).is_err());
         assert!(MultiplicativeCipher::new(26).is_err());
+        assert_eq!(
+            MultiplicativeCipher::new(13).err(),
+            Some(CipherError::NonInvertibleKey { value: 13, modulus: 26 })
+        );
     }
 
     #[test]
@@ -73,7 +104,8 @@ mod multiplicative_tests {
     fn decrypt_succeeds() {
         MultiplicativeCipher::new(3)
             .unwrap()
-            .decrypt("ESMOM OEECU OHSWS K".to_owned());
+            .decrypt("ESMOM OEECU OHSWS K".to_owned())
+            .unwrap();
     }
 
     #[test]
@@ -82,7 +114,15 @@ mod multiplicative_tests {
         let cipher = MultiplicativeCipher::new(3).unwrap();
         let encrypted_message = cipher.encrypt(initial_message.clone());
         assert_eq!(encrypted_message, "HXAEA EESMO QOABL MOEEC UO".to_owned());
-        let decrypted_message = cipher.decrypt(encrypted_message.clone());
-        assert_eq!(decrypted_message, "THISISSOMEWEIRDMESSAGE".to_owned());
+        let decrypted_message = cipher.decrypt(encrypted_message);
+        assert_eq!(decrypted_message, Ok("THISISSOMEWEIRDMESSAGE".to_owned()));
+    }
+
+    #[test]
+    fn key_validity_depends_on_alphabet() {
+        let russian = Alphabet::new("АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ").unwrap();
+        assert!(MultiplicativeCipher::with_alphabet(2, russian.clone()).is_ok());
+        assert!(MultiplicativeCipher::with_alphabet(3, russian.clone()).is_err());
+        assert!(MultiplicativeCipher::with_alphabet(11, russian).is_err());
     }
 }
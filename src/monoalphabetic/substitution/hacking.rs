@@ -0,0 +1,174 @@
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, climb, Annealing, Cooling};
+use crate::random::Random;
+use crate::scoring::{default_scorer, Scorer};
+
+const COOLING: Cooling = Cooling::Linear(20.0);
+
+/// Breaks the substitution cipher with simulated annealing: starting from the key that matches the
+/// letter frequencies, two letters of the key are swapped at every step, a better key is always kept,
+/// a worse one sometimes (less and less often while the temperature goes down), so the search can get
+/// out of local maximums. The search is restarted from random keys and the best key is kept.
+pub struct SubstitutionHack {
+    // Positions of the letters in the alphabet
+    encrypted_message: Vec<usize>,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+}
+
+impl SubstitutionHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+        let modulus = alphabet.modulus();
+        SubstitutionHack {
+            encrypted_message: alphabet
+                .to_indices(&encrypted_message)
+                .into_iter()
+                .map(|x| ((x + modulus - first) % modulus) as usize)
+                .collect(),
+            alphabet,
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+        }
+    }
+
+    annealing_builders!(5, 20000);
+
+    /// The best key found by every search (as the ciphertext alphabet, so it can be given to
+    /// `SubstitutionCipher::new`) with the decrypted message and its score, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(String, String, f64)> {
+        match &self.scorer {
+            Some(scorer) => self.ranked_variants_with(scorer.as_ref()),
+            None => self.ranked_variants_with(default_scorer(&self.alphabet).as_ref()),
+        }
+    }
+
+    /// `get_ranked_variants` with a borrowed scorer, for the hacks that finish with a substitution
+    pub(crate) fn ranked_variants_with(&self, scorer: &dyn Scorer) -> Vec<(String, String, f64)> {
+        if self.encrypted_message.is_empty() {
+            return vec![];
+        }
+        let mut random = Random::new(self.annealing.seed);
+        // Scores are per letter, so they are scaled to the message to make the temperature meaningful
+        let scale = self.encrypted_message.len() as f64;
+        let mut variants: Vec<(String, String, f64)> = vec![];
+        for restart in 0..self.annealing.restarts {
+            let mut key = self.frequency_key();
+            if restart > 0 {
+                random.shuffle(&mut key);
+            }
+            let (key, _) = self.annealing.anneal(
+                key,
+                COOLING,
+                &mut random,
+                |key| scorer.score(&self.decrypt(key)) * scale,
+                |key, random| {
+                    let (i, j) = (random.below(key.len()), random.below(key.len()));
+                    key.swap(i, j);
+                    i != j
+                },
+            );
+            let score = scorer.score(&self.decrypt(&key));
+            let (key, score) = climb(key, score, |key| scorer.score(&self.decrypt(key)));
+            let variant = (self.encryption_key(&key), self.decrypt(&key), score);
+            if !variants.iter().any(|v| v.0 == variant.0) {
+                variants.push(variant);
+            }
+        }
+        variants.sort_by(|a, b| b.2.total_cmp(&a.2));
+        variants
+    }
+
+    pub fn best(&self) -> Option<(String, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+
+    /// The plaintext letter of every ciphertext letter, from the letters of the message
+    /// sorted by their counts matched with the letters of the language sorted by their frequencies
+    fn frequency_key(&self) -> Vec<usize> {
+        let modulus = self.alphabet.modulus() as usize;
+        let mut counts = vec![0; modulus];
+        for x in &self.encrypted_message {
+            counts[*x] += 1;
+        }
+        let mut encrypted = (0..modulus).collect::<Vec<usize>>();
+        encrypted.sort_by(|a, b| counts[*b].cmp(&counts[*a]));
+        let symbols = self.alphabet.symbols();
+        let mut language = self
+            .alphabet
+            .most_frequent_symbols()
+            .into_iter()
+            .map(|s| symbols.iter().position(|x| *x == s).unwrap())
+            .collect::<Vec<usize>>();
+        if language.is_empty() {
+            language = (0..modulus).collect();
+        }
+        let mut key = vec![0; modulus];
+        for (cipher_letter, plain_letter) in encrypted.into_iter().zip(language) {
+            key[cipher_letter] = plain_letter;
+        }
+        key
+    }
+
+    fn decrypt(&self, key: &[usize]) -> String {
+        let symbols = self.alphabet.symbols();
+        self.encrypted_message.iter().map(|x| symbols[key[*x]]).collect()
+    }
+
+    /// The ciphertext letter of every plaintext letter
+    fn encryption_key(&self, key: &[usize]) -> String {
+        let mut encryption = vec![' '; key.len()];
+        for (cipher_letter, plain_letter) in key.iter().enumerate() {
+            encryption[*plain_letter] = self.alphabet.symbols()[cipher_letter];
+        }
+        encryption.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod substitution_hack_test {
+    use crate::cipher::Cipher;
+    use crate::monoalphabetic::substitution::hacking::SubstitutionHack;
+    use crate::monoalphabetic::substitution::manipulations::SubstitutionCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to heaven, we were all going direct the other way";
+
+    #[test]
+    fn best_breaks_random_key() {
+        let cipher = SubstitutionCipher::random(12);
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        for seed in 10..15 {
+            let (_, message, _) = SubstitutionHack::new(encrypted.clone()).with_seed(seed).best().unwrap();
+            assert_eq!(message, cipher.decrypt(encrypted.clone()).unwrap(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_result() {
+        let encrypted = SubstitutionCipher::from_keyword("dickens", 'a').unwrap().encrypt(MESSAGE.to_owned());
+        let hack = SubstitutionHack::new(encrypted.clone()).with_restarts(2).with_iterations(2000);
+        let first = hack.with_seed(5).get_ranked_variants();
+        let hack = SubstitutionHack::new(encrypted).with_restarts(2).with_iterations(2000);
+        assert_eq!(first, hack.with_seed(5).get_ranked_variants());
+        assert!(first.windows(2).all(|pair| pair[0].2 >= pair[1].2));
+    }
+
+    #[test]
+    fn found_key_decrypts_the_message() {
+        let cipher = SubstitutionCipher::atbash();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        let (key, message, _) = SubstitutionHack::new(encrypted.clone()).with_restarts(1).best().unwrap();
+        assert_eq!(message, cipher.decrypt(encrypted.clone()).unwrap());
+        assert_eq!(SubstitutionCipher::new(&key).unwrap().decrypt(encrypted), Ok(message));
+        assert!(SubstitutionHack::new("".to_owned()).best().is_none());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::random::Random;
+
+/// Every letter is replaced by the letter in the same position of the key,
+/// which is a permutation of the alphabet (the ciphertext alphabet)
+pub struct SubstitutionCipher {
+    key: String,
+    // The ciphertext index of every plaintext index and back
+    encryption: Vec<u32>,
+    decryption: Vec<u32>,
+    alphabet: Alphabet,
+}
+
+impl SubstitutionCipher {
+    /// The key is the ciphertext alphabet, like "QWERTYUIOPASDFGHJKLZXCVBNM" for A..Z
+    pub fn new(key: &str) -> Result<Self, CipherError> {
+        Self::with_alphabet(key, Alphabet::english())
+    }
+
+    pub fn with_alphabet(key: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let modulus = alphabet.modulus();
+        let key = alphabet.to_indices_strict(key)?;
+        if key.len() != modulus as usize {
+            return Err(CipherError::InvalidKey(format!(
+                "the key has {} letters instead of {}",
+                key.len(),
+                modulus
+            )));
+        }
+        let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+        let mut encryption = vec![0; modulus as usize];
+        let mut decryption = vec![None; modulus as usize];
+        for (position, cipher_index) in key.iter().enumerate() {
+            let plain_index = (position as u32 + first) % modulus;
+            if decryption[*cipher_index as usize].is_some() {
+                return Err(CipherError::InvalidKey(format!(
+                    "{} is repeated in the key",
+                    alphabet.symbol_at(*cipher_index)
+                )));
+            }
+            encryption[plain_index as usize] = *cipher_index;
+            decryption[*cipher_index as usize] = Some(plain_index);
+        }
+        Ok(SubstitutionCipher {
+            key: alphabet.from_indices(&key),
+            encryption,
+            decryption: decryption.into_iter().map(|x| x.unwrap()).collect(),
+            alphabet,
+        })
+    }
+
+    /// The mixed alphabet of the keyword (its letters without repetitions, then the remaining
+    /// letters of the alphabet) written starting under the `start` letter, so with start 'A'
+    /// the keyword "zebras" gives "ZEBRASCDFGHIJKLMNOPQTUVWXY"
+    pub fn from_keyword(keyword: &str, start: char) -> Result<Self, CipherError> {
+        Self::from_keyword_with_alphabet(keyword, start, Alphabet::english())
+    }
+
+    pub fn from_keyword_with_alphabet(keyword: &str, start: char, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let mut mixed = vec![];
+        for symbol in alphabet.from_indices(&alphabet.to_indices_strict(keyword)?).chars() {
+            if !mixed.contains(&symbol) {
+                mixed.push(symbol);
+            }
+        }
+        for symbol in alphabet.symbols() {
+            if !mixed.contains(symbol) {
+                mixed.push(*symbol);
+            }
+        }
+        let start = Self::position(start, &alphabet)?;
+        mixed.rotate_right(start);
+        Self::with_alphabet(&mixed.into_iter().collect::<String>(), alphabet)
+    }
+
+    /// A random permutation of the alphabet, the same seed gives the same key
+    pub fn random(seed: u64) -> Self {
+        Self::random_with_alphabet(seed, Alphabet::english())
+    }
+
+    pub fn random_with_alphabet(seed: u64, alphabet: Alphabet) -> Self {
+        let mut symbols = alphabet.symbols().to_vec();
+        Random::new(seed).shuffle(&mut symbols);
+        Self::with_alphabet(&symbols.into_iter().collect::<String>(), alphabet).unwrap()
+    }
+
+    /// The reversed alphabet, A becomes Z, B becomes Y and so on
+    pub fn atbash() -> Self {
+        Self::atbash_with_alphabet(Alphabet::english())
+    }
+
+    pub fn atbash_with_alphabet(alphabet: Alphabet) -> Self {
+        let key = alphabet.symbols().iter().rev().collect::<String>();
+        Self::with_alphabet(&key, alphabet).unwrap()
+    }
+
+    /// Caesar cipher with the shift 13, encrypting twice gives the original message
+    pub fn rot13() -> Self {
+        let mut key = Alphabet::english().symbols().to_vec();
+        key.rotate_left(13);
+        Self::new(&key.into_iter().collect::<String>()).unwrap()
+    }
+
+    fn position(symbol: char, alphabet: &Alphabet) -> Result<usize, CipherError> {
+        let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+        let index = alphabet.index_of(symbol).ok_or(CipherError::SymbolNotInAlphabet(symbol))?;
+        Ok(((index + alphabet.modulus() - first) % alphabet.modulus()) as usize)
+    }
+
+    fn substitute(&self, message: &str, table: &[u32]) -> String {
+        self.alphabet.from_indices(
+            &self
+                .alphabet
+                .to_indices(message)
+                .into_iter()
+                .map(|x| table[x as usize])
+                .collect::<Vec<u32>>(),
+        )
+    }
+}
+
+impl Cipher for SubstitutionCipher {
+    type Key = String;
+
+    fn name(&self) -> &'static str {
+        "Substitution"
+    }
+
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&self.substitute(&message, &self.encryption))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        Ok(self.substitute(&encrypted_message, &self.decryption))
+    }
+}
+
+#[cfg(test)]
+mod substitution_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::monoalphabetic::substitution::manipulations::SubstitutionCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = SubstitutionCipher::new("QWERTYUIOPASDFGHJKLZXCVBNM").unwrap();
+        assert_eq!(cipher.encrypt("Hello world".to_owned()), "ITSSG VGKSR".to_owned());
+        assert_eq!(cipher.decrypt("ITSSG VGKSR".to_owned()), Ok("HELLOWORLD".to_owned()));
+    }
+
+    #[test]
+    fn keyword_alphabets_work() {
+        let cipher = SubstitutionCipher::from_keyword("zebras", 'a').unwrap();
+        assert_eq!(cipher.key(), "ZEBRASCDFGHIJKLMNOPQTUVWXY".to_owned());
+        assert_eq!(cipher.encrypt("flee at once".to_owned()), "SIAAZ QLKBA".to_owned());
+        let cipher = SubstitutionCipher::from_keyword("zebras", 'd').unwrap();
+        assert_eq!(cipher.key(), "WXYZEBRASCDFGHIJKLMNOPQTUV".to_owned());
+        assert_eq!(
+            SubstitutionCipher::from_keyword("zebras", '1').err(),
+            Some(CipherError::SymbolNotInAlphabet('1'))
+        );
+    }
+
+    #[test]
+    fn presets_work() {
+        assert_eq!(SubstitutionCipher::atbash().encrypt("wizard".to_owned()), "DRAZI W".to_owned());
+        let rot13 = SubstitutionCipher::rot13();
+        assert_eq!(rot13.encrypt("Hello".to_owned()), "URYYB".to_owned());
+        assert_eq!(rot13.encrypt(rot13.encrypt("Hello".to_owned())), "HELLO".to_owned());
+    }
+
+    #[test]
+    fn random_keys_are_reproducible() {
+        let cipher = SubstitutionCipher::random(2024);
+        assert_eq!(cipher.key(), SubstitutionCipher::random(2024).key());
+        assert_ne!(cipher.key(), SubstitutionCipher::random(2025).key());
+        let encrypted = cipher.encrypt("Meet me at the mall".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("MEETMEATTHEMALL".to_owned()));
+    }
+
+    #[test]
+    fn works_with_other_alphabets() {
+        let cipher = SubstitutionCipher::from_keyword_with_alphabet("ελληνικά", 'α', Alphabet::greek()).unwrap();
+        assert_eq!(cipher.key(), "ΕΛΗΝΙΚΑΒΓΔΖΘΜΞΟΠΡΣΤΥΦΧΨΩ".to_owned());
+        let encrypted = cipher.encrypt("Καλημέρα".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("ΚΑΛΗΜΕΡΑ".to_owned()));
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        assert_eq!(
+            SubstitutionCipher::new("ABC").err(),
+            Some(CipherError::InvalidKey("the key has 3 letters instead of 26".to_owned()))
+        );
+        assert_eq!(
+            SubstitutionCipher::new("AACDEFGHIJKLMNOPQRSTUVWXYZ").err(),
+            Some(CipherError::InvalidKey("A is repeated in the key".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, Annealing};
+use crate::error::CipherError;
+use crate::polybius::search::{self, digraphs, square_scorer, SquareSearch};
+use crate::polybius::{merged_alphabet, square_size, Squares};
+use crate::polyalphabetic::four_square::manipulations::replace_digraphs;
+use crate::scoring::Scorer;
+
+/// Breaks the four-square cipher with a hill-climb over both keyed squares, the same search as
+/// `PlayfairHack` uses: one of the squares is changed a little at every step and worse squares
+/// are sometimes kept while the temperature goes down.
+///
+/// A crib (known plaintext at a known position) helps a lot, the squares are first changed
+/// until they decrypt it. Two keyed squares need longer messages than Playfair.
+pub struct FourSquareHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+    crib: Option<(String, usize)>,
+}
+
+impl FourSquareHack {
+    /// 5×5 squares with J written as I
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, merged_alphabet('J', 'I').unwrap())
+    }
+
+    /// The alphabet of the squares, like `polybius::alphanumeric_alphabet()` for 6×6 squares
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        FourSquareHack {
+            encrypted_message,
+            alphabet: alphabet.one_based(false),
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+            crib: None,
+        }
+    }
+
+    annealing_builders!(5, 200000);
+
+    /// Known plaintext starting at the given letter of the message
+    pub fn with_crib(mut self, crib: &str, position: usize) -> Self {
+        self.crib = Some((crib.to_owned(), position));
+        self
+    }
+
+    /// The keyed squares found by every search (the upper right one first, row by row, so they can be given to `PolybiusSquare::new`)
+    /// with the decrypted message and its score, the best one first
+    pub fn get_ranked_variants(&self) -> Result<Vec<(Squares, String, f64)>, CipherError> {
+        let message = digraphs(&self.encrypted_message, &self.alphabet)?;
+        let size = square_size(self.alphabet.modulus() as usize)?;
+        let crib = match &self.crib {
+            Some((crib, position)) => Some((search::crib(crib, *position, &message, &self.alphabet)?, *position)),
+            None => None,
+        };
+        let default;
+        let scorer = match &self.scorer {
+            Some(scorer) => scorer.as_ref(),
+            None => {
+                default = square_scorer(&self.alphabet);
+                default.as_ref()
+            }
+        };
+        let search = SquareSearch {
+            message: &message,
+            size,
+            squares: 2,
+            symbols: self.alphabet.symbols(),
+            scorer,
+            crib: crib.as_ref().map(|(crib, position)| (crib.as_slice(), *position)),
+            temperatures: search::TEMPERATURES,
+            decrypt: &|cells, message| replace_digraphs(cells, size, message, true),
+        };
+        let symbols = self.alphabet.symbols();
+        let to_string = |cells: &[usize]| cells.iter().map(|x| symbols[*x]).collect::<String>();
+        Ok(search
+            .run(&self.annealing)
+            .into_iter()
+            .map(|(cells, text, score)| {
+                let (first, second) = cells.split_at(size * size);
+                ((to_string(first), to_string(second)), text, score)
+            })
+            .collect())
+    }
+
+    pub fn best(&self) -> Result<(Squares, String, f64), CipherError> {
+        self.get_ranked_variants()?.into_iter().next().ok_or(CipherError::EmptyInput)
+    }
+}
+
+#[cfg(test)]
+mod four_square_hack_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::{merged_alphabet, PolybiusSquare};
+    use crate::polyalphabetic::four_square::hacking::FourSquareHack;
+    use crate::polyalphabetic::four_square::manipulations::FourSquareCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way";
+
+    fn cipher() -> FourSquareCipher {
+        // The searches start from squares shuffled with their seed like these, so they are tried with other seeds
+        let square = |seed| PolybiusSquare::random(seed, merged_alphabet('J', 'I').unwrap()).unwrap();
+        FourSquareCipher::with_squares(square(4), square(5)).unwrap()
+    }
+
+    /// The letters decrypted wrong, leaving out the pairs of encrypted letters seen only once in the message:
+    /// nothing else tells what they decrypt to, so they decrypt to the pair the scorer likes best
+    fn wrong(encrypted: &str, message: &str, expected: &str) -> usize {
+        let pairs = |text: &str| {
+            let letters = text.chars().filter(|c| *c != ' ').collect::<Vec<char>>();
+            letters.chunks(2).map(|pair| pair.to_vec()).collect::<Vec<Vec<char>>>()
+        };
+        let encrypted = pairs(encrypted);
+        let seen_again = |pair: &Vec<char>| encrypted.iter().filter(|other| *other == pair).count() > 1;
+        encrypted
+            .iter()
+            .zip(pairs(message).iter().zip(pairs(expected)))
+            .filter(|(pair, (found, expected))| seen_again(pair) && *found != expected)
+            .count()
+    }
+
+    #[test]
+    fn crib_breaks_random_squares() {
+        let encrypted = cipher().encrypt(MESSAGE.to_owned());
+        let expected = cipher().decrypt(encrypted.clone()).unwrap();
+        for seed in 10..15 {
+            let hack = FourSquareHack::new(encrypted.clone())
+                .with_seed(seed)
+                .with_restarts(1)
+                .with_iterations(30000)
+                .with_crib("ITWASTHEBESTOFTIMESITWASTHEWORST", 0);
+            let ((first, second), message, _) = hack.best().unwrap();
+            assert_eq!(wrong(&encrypted, &message, &expected), 0, "seed {}: {}", seed, message);
+            let found =
+                FourSquareCipher::with_squares(PolybiusSquare::new(&first).unwrap(), PolybiusSquare::new(&second).unwrap())
+                    .unwrap();
+            assert_eq!(found.decrypt(encrypted.clone()), Ok(message));
+        }
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(FourSquareHack::new("".to_owned()).best(), Err(CipherError::EmptyInput));
+        assert_eq!(
+            FourSquareHack::new("ABC".to_owned()).best(),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::polybius::{filler, positions, PolybiusSquare};
+
+/// Four squares in a 2×2 grid: the upper left and the lower right ones have the alphabet in order,
+/// the upper right and the lower left ones are keyed. The first letter of every pair is found in
+/// the upper left square and the second one in the lower right square, they are replaced by the
+/// letters in the other corners of their rectangle, from the upper right square first.
+/// A message of odd length gets an X at the end.
+pub struct FourSquareCipher {
+    first: PolybiusSquare,
+    second: PolybiusSquare,
+}
+
+impl FourSquareCipher {
+    /// 5×5 squares of the keywords (the upper right and the lower left ones), J is written as I
+    pub fn new(first_keyword: &str, second_keyword: &str) -> Result<Self, CipherError> {
+        Self::with_squares(
+            PolybiusSquare::from_keyword(first_keyword)?,
+            PolybiusSquare::from_keyword(second_keyword)?,
+        )
+    }
+
+    /// The keyed squares, they should have the same alphabet
+    pub fn with_squares(first: PolybiusSquare, second: PolybiusSquare) -> Result<Self, CipherError> {
+        if first.alphabet() != second.alphabet() {
+            return Err(CipherError::InvalidKey("the squares have different alphabets".to_owned()));
+        }
+        Ok(FourSquareCipher { first, second })
+    }
+
+    fn to_string(&self, indices: &[usize]) -> String {
+        indices.iter().map(|x| self.first.alphabet().symbols()[*x]).collect()
+    }
+
+    fn cells(&self) -> Vec<usize> {
+        [self.first.cells(), self.second.cells()].concat()
+    }
+}
+
+/// Replaces the pairs of symbols (positions in the alphabet) using the keyed squares given as
+/// the symbols of their cells, the upper right square and then the lower left one
+pub(crate) fn replace_digraphs(squares: &[usize], size: usize, message: &[usize], decrypt: bool) -> Vec<usize> {
+    let (first, second) = squares.split_at(size * size);
+    // The squares with the alphabet in order have every symbol in the cell with its number
+    let plain = (0..size * size).collect::<Vec<usize>>();
+    let (a_positions, b_positions, a_output, b_output) = if decrypt {
+        (positions(first), positions(second), &plain[..], &plain[..])
+    } else {
+        (plain.clone(), plain.clone(), first, second)
+    };
+    let mut result = Vec::with_capacity(message.len());
+    for pair in message.chunks_exact(2) {
+        let (a, b) = (a_positions[pair[0]], b_positions[pair[1]]);
+        let (row_a, column_a, row_b, column_b) = (a / size, a % size, b / size, b % size);
+        result.push(a_output[row_a * size + column_b]);
+        result.push(b_output[row_b * size + column_a]);
+    }
+    result
+}
+
+impl Cipher for FourSquareCipher {
+    /// The keyed squares row by row
+    type Key = (String, String);
+
+    fn name(&self) -> &'static str {
+        "Four-square"
+    }
+
+    fn key(&self) -> (String, String) {
+        (self.first.key(), self.second.key())
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        self.first.alphabet()
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let mut letters = self.first.indices(&message);
+        if !letters.len().is_multiple_of(2) {
+            letters.push(filler(self.first.alphabet()));
+        }
+        let encrypted = replace_digraphs(&self.cells(), self.first.size(), &letters, false);
+        Grouping::default().format(&self.to_string(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.first.indices(&encrypted_message);
+        if !encrypted_message.len().is_multiple_of(2) {
+            return Err(CipherError::InvalidLength {
+                length: encrypted_message.len(),
+                block_size: 2,
+            });
+        }
+        let decrypted = replace_digraphs(&self.cells(), self.first.size(), &encrypted_message, true);
+        Ok(self.to_string(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod four_square_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::PolybiusSquare;
+    use crate::polyalphabetic::four_square::manipulations::FourSquareCipher;
+
+    // The squares of the examples on Wikipedia leave out Q
+    fn example() -> FourSquareCipher {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPRSTUVWXYZ").unwrap();
+        FourSquareCipher::with_squares(
+            PolybiusSquare::from_keyword_with_alphabet("example", alphabet.clone()).unwrap(),
+            PolybiusSquare::from_keyword_with_alphabet("keyword", alphabet).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = example();
+        assert_eq!(cipher.encrypt("Help me Obi Wan Kenobi".to_owned()), "FYGMK YHOBX MFKKK IMD");
+        assert_eq!(cipher.encrypt("Odd".to_owned()), cipher.encrypt("oddx".to_owned()));
+    }
+
+    #[test]
+    fn decrypt_works() {
+        let cipher = example();
+        assert_eq!(
+            cipher.decrypt("FYGMK YHOBX MFKKK IMD".to_owned()),
+            Ok("HELPMEOBIWANKENOBI".to_owned())
+        );
+        let cipher = FourSquareCipher::with_squares(
+            PolybiusSquare::alphanumeric("agent").unwrap(),
+            PolybiusSquare::alphanumeric("007").unwrap(),
+        )
+        .unwrap();
+        let encrypted = cipher.encrypt("Meet at 10:45".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("MEETAT1045".to_owned()));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let cipher = FourSquareCipher::new("example", "keyword").unwrap();
+        assert_eq!(
+            cipher.decrypt("FYG".to_owned()),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+        assert!(FourSquareCipher::with_squares(
+            PolybiusSquare::from_keyword("example").unwrap(),
+            PolybiusSquare::alphanumeric("keyword").unwrap(),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::modular::{linear, Matrix};
+use crate::polyalphabetic::hill::manipulations::HillCipher;
+
+/// The most keys that are checked against the known plaintext, more are reported as an error
+const MAX_KEYS: usize = 100000;
+
+/// Known-plaintext attack on the Hill cipher.
+///
+/// With the plaintext blocks p1..pn as the columns of P and the ciphertext blocks as the columns
+/// of C, the key is K = C * P^-1. When no n blocks of the known plaintext form an invertible
+/// matrix, every row of the key is found by solving the linear system separately by the prime
+/// factors of the modulus, giving all the keys consistent with the known plaintext
+/// (up to `MAX_KEYS` of them, a plaintext that fits more keys doesn't say much about the key anyway).
+pub struct HillCipherHack {
+    encrypted_message: Vec<u32>,
+    block_size: usize,
+    alphabet: Alphabet,
+}
+
+impl HillCipherHack {
+    pub fn new(encrypted_message: String, block_size: usize) -> Result<Self, CipherError> {
+        Self::with_alphabet(encrypted_message, block_size, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, block_size: usize, alphabet: Alphabet) -> Result<Self, CipherError> {
+        if block_size == 0 {
+            return Err(CipherError::InvalidKey("the block size should be at least 1".to_owned()));
+        }
+        Ok(HillCipherHack {
+            encrypted_message: alphabet.to_indices(&encrypted_message),
+            block_size,
+            alphabet,
+        })
+    }
+
+    /// All keys which encrypt the plaintext into the ciphertext, both should start from the beginning of a block.
+    /// At least n full blocks (n*n letters) are needed, fewer leave whole rows of the key unknown
+    pub fn find_keys(&self, plaintext: &str, ciphertext: &str) -> Result<Vec<Matrix>, CipherError> {
+        let plaintext = self.alphabet.to_indices_strict(plaintext)?;
+        let ciphertext = self.alphabet.to_indices_strict(ciphertext)?;
+        if plaintext.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        let n = self.block_size;
+        let length = plaintext.len().min(ciphertext.len());
+        if length < n * n {
+            return Err(CipherError::CribTooShort {
+                crib_length: length,
+                min_length: n * n,
+            });
+        }
+        self.keys_for_blocks(
+            &Self::to_blocks(&plaintext[..length], n),
+            &Self::to_blocks(&ciphertext[..length], n),
+        )
+    }
+
+    /// When you know what content the decrypted message can have, you can provide it as a crib,
+    /// which is checked at every position of the message.
+    /// The crib should cover at least n full blocks, so n*n + n - 1 letters are enough for any position.
+    pub fn check_with_crib(&self, crib: String) -> Result<Vec<(Matrix, String)>, CipherError> {
+        let crib = self.alphabet.to_indices_strict(&crib)?;
+        let n = self.block_size;
+        if crib.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        if crib.len() > self.encrypted_message.len() {
+            return Err(CipherError::CribTooLong {
+                crib_length: crib.len(),
+                message_length: self.encrypted_message.len(),
+            });
+        }
+        if !self.encrypted_message.len().is_multiple_of(n) {
+            return Err(CipherError::InvalidLength {
+                length: self.encrypted_message.len(),
+                block_size: n,
+            });
+        }
+        let mut results = vec![];
+        for position in 0..=(self.encrypted_message.len() - crib.len()) {
+            // Only the blocks fully covered by the crib can be used
+            let first = position.div_ceil(n) * n;
+            let last = (position + crib.len()) / n * n;
+            if last < first + n * n {
+                continue;
+            }
+            // The blocks of the crib here fit too many keys to say anything about the key
+            let keys = match self.keys_for_blocks(
+                &Self::to_blocks(&crib[first - position..last - position], n),
+                &Self::to_blocks(&self.encrypted_message[first..last], n),
+            ) {
+                Ok(keys) => keys,
+                Err(_) => continue,
+            };
+            for key in keys {
+                let cipher = match HillCipher::with_alphabet(key.clone(), self.alphabet.clone()) {
+                    Ok(cipher) => cipher,
+                    Err(_) => continue,
+                };
+                let message = cipher.decrypt(self.alphabet.from_indices(&self.encrypted_message))?;
+                let decrypted_crib = message.chars().skip(position).take(crib.len()).collect::<String>();
+                if decrypted_crib == self.alphabet.from_indices(&crib) {
+                    results.push((key, message));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    fn keys_for_blocks(&self, plaintext: &[Vec<i64>], ciphertext: &[Vec<i64>]) -> Result<Vec<Matrix>, CipherError> {
+        let modulus = self.alphabet.modulus() as i64;
+        let keys = match self.find_invertible_blocks(plaintext) {
+            Some(chosen) => {
+                // K = C * P^-1, the key is unique
+                let p = Self::columns(chosen.iter().map(|i| plaintext[*i].clone()).collect());
+                let c = Self::columns(chosen.iter().map(|i| ciphertext[*i].clone()).collect());
+                vec![c.multiply(&p.inverse(modulus).unwrap(), modulus)]
+            }
+            None => self.solve_rows(plaintext, ciphertext)?,
+        };
+        Ok(keys
+            .into_iter()
+            .filter(|key| {
+                plaintext
+                    .iter()
+                    .zip(ciphertext)
+                    .all(|(p, c)| key.multiply_vector(p, modulus) == *c)
+            })
+            .filter(|key| HillCipher::with_alphabet(key.clone(), self.alphabet.clone()).is_ok())
+            .collect())
+    }
+
+    /// Indices of n plaintext blocks which form an invertible matrix
+    fn find_invertible_blocks(&self, plaintext: &[Vec<i64>]) -> Option<Vec<usize>> {
+        let n = self.block_size;
+        if plaintext.len() < n {
+            return None;
+        }
+        let modulus = self.alphabet.modulus() as i64;
+        let mut chosen = (0..n).collect::<Vec<usize>>();
+        loop {
+            let p = Self::columns(chosen.iter().map(|i| plaintext[*i].clone()).collect());
+            if p.inverse(modulus).is_ok() {
+                return Some(chosen);
+            }
+            if !Self::next_combination(&mut chosen, plaintext.len()) {
+                return None;
+            }
+        }
+    }
+
+    /// Every row of the key gives a linear system: row * p = c[row] for every block.
+    /// The keys are all the combinations of the solutions of the rows
+    fn solve_rows(&self, plaintext: &[Vec<i64>], ciphertext: &[Vec<i64>]) -> Result<Vec<Matrix>, CipherError> {
+        let modulus = self.alphabet.modulus() as i64;
+        let rows = (0..self.block_size)
+            .map(|row| {
+                let values = ciphertext.iter().map(|c| c[row]).collect::<Vec<i64>>();
+                linear::solve(plaintext, &values, modulus)
+            })
+            .collect::<Vec<Vec<Vec<i64>>>>();
+        let count = rows.iter().fold(1usize, |count, solutions| count.saturating_mul(solutions.len()));
+        if count > MAX_KEYS {
+            return Err(CipherError::TooManyKeys { count, limit: MAX_KEYS });
+        }
+        Ok(rows
+            .into_iter()
+            .fold(vec![vec![]], |keys: Vec<Vec<i64>>, rows| {
+                keys.iter()
+                    .flat_map(|key| {
+                        rows.iter().map(move |row| {
+                            let mut key = key.clone();
+                            key.extend(row);
+                            key
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .filter_map(|key| Matrix::new(key).ok())
+            .collect())
+    }
+
+    fn to_blocks(message: &[u32], block_size: usize) -> Vec<Vec<i64>> {
+        message
+            .chunks_exact(block_size)
+            .map(|block| block.iter().map(|x| *x as i64).collect())
+            .collect()
+    }
+
+    /// The matrix with the given vectors as its columns
+    fn columns(vectors: Vec<Vec<i64>>) -> Matrix {
+        Matrix::from_rows(vectors).unwrap().transpose()
+    }
+
+    /// Moves to the next k-combination of 0..total in the lexicographic order
+    fn next_combination(combination: &mut [usize], total: usize) -> bool {
+        let k = combination.len();
+        let i = match (0..k).rev().find(|i| combination[*i] < total - k + i) {
+            Some(i) => i,
+            None => return false,
+        };
+        combination[i] += 1;
+        for j in i + 1..k {
+            combination[j] = combination[j - 1] + 1;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod hill_cipher_hack_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::modular::Matrix;
+    use crate::polyalphabetic::hill::hacking::HillCipherHack;
+    use crate::polyalphabetic::hill::manipulations::HillCipher;
+
+    #[test]
+    fn find_keys_with_invertible_plaintext() {
+        let key = Matrix::new(vec![5, 3, 9, 6]).unwrap();
+        let cipher = HillCipher::new(key.clone()).unwrap();
+        let encrypted = cipher.encrypt("meet".to_owned());
+        let hack = HillCipherHack::new(encrypted.clone(), 2).unwrap();
+        assert_eq!(hack.find_keys("meet", &encrypted), Ok(vec![key]));
+    }
+
+    #[test]
+    fn find_keys_with_non_invertible_plaintext() {
+        // The plaintext matrix of "ABAB" isn't invertible, so every key which works for it is found
+        let key = Matrix::new(vec![5, 3, 9, 6]).unwrap();
+        let encrypted = HillCipher::new(key.clone()).unwrap().encrypt("ABAB".to_owned());
+        let keys = HillCipherHack::new(encrypted.clone(), 2).unwrap().find_keys("ABAB", &encrypted).unwrap();
+        assert!(keys.contains(&key));
+        assert!(keys.len() > 1);
+        for other in keys {
+            let other = HillCipher::new(other).unwrap();
+            assert_eq!(other.encrypt("ABAB".to_owned()), encrypted);
+        }
+    }
+
+    #[test]
+    fn check_with_crib_finds_trigraph_key() {
+        let key = Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap();
+        let cipher = HillCipher::new(key.clone()).unwrap();
+        let encrypted = cipher.encrypt("The treasure is buried under the old oak tree".to_owned());
+        let results = HillCipherHack::new(encrypted, 3)
+            .unwrap()
+            .check_with_crib("buried under the".to_owned())
+            .unwrap();
+        assert!(results.contains(&(key, "THETREASUREISBURIEDUNDERTHEOLDOAKTREEXX".to_owned())));
+    }
+
+    #[test]
+    fn short_cribs_give_no_results() {
+        let encrypted = HillCipher::new(Matrix::new(vec![5, 3, 9, 6]).unwrap())
+            .unwrap()
+            .encrypt("Meet you at the mall".to_owned());
+        assert_eq!(HillCipherHack::new(encrypted, 2).unwrap().check_with_crib("at".to_owned()), Ok(vec![]));
+    }
+
+    #[test]
+    fn short_known_plaintext_is_rejected() {
+        // One block of a 3×3 key leaves 26^3 choices for every row
+        let encrypted = HillCipher::new(Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap())
+            .unwrap()
+            .encrypt("buried under the old oak".to_owned());
+        let hack = HillCipherHack::new(encrypted.clone(), 3).unwrap();
+        assert_eq!(
+            hack.find_keys("bur", &encrypted),
+            Err(CipherError::CribTooShort { crib_length: 3, min_length: 9 })
+        );
+        // Blocks of Z (0 in the one-based alphabet) only don't restrict the key at all
+        let encrypted = HillCipher::new(Matrix::new(vec![5, 3, 9, 6]).unwrap()).unwrap().encrypt("ZZZZ".to_owned());
+        assert_eq!(
+            HillCipherHack::new(encrypted.clone(), 2).unwrap().find_keys("ZZZZ", &encrypted),
+            Err(CipherError::TooManyKeys { count: 456976, limit: 100000 })
+        );
+    }
+
+    #[test]
+    fn zero_block_size_is_rejected() {
+        assert_eq!(
+            HillCipherHack::new("BQGI".to_owned(), 0).err(),
+            Some(CipherError::InvalidKey("the block size should be at least 1".to_owned()))
+        );
+    }
+}
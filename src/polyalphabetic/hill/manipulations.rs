@@ -0,0 +1,173 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::modular::{gcd, Matrix};
+
+/// Hill cipher with n×n key matrix, every block of n letters is multiplied by the key
+pub struct HillCipher {
+    key: Matrix,
+    inverse: Matrix,
+    alphabet: Alphabet,
+    filler: u32,
+}
+
+impl HillCipher {
+    pub fn new(key: Matrix) -> Result<Self, CipherError> {
+        Self::with_alphabet(key, Alphabet::english())
+    }
+
+    pub fn with_alphabet(key: Matrix, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let modulus = alphabet.modulus() as i64;
+        let key = key.reduce(modulus);
+        // The key can be inverted only if its determinant is coprime with the modulus
+        let determinant = key.determinant(modulus);
+        if gcd(determinant, modulus) != 1 {
+            return Err(CipherError::NonInvertibleKey {
+                value: determinant,
+                modulus: modulus as u32,
+            });
+        }
+        let inverse = key.inverse(modulus)?;
+        // X if the alphabet has it, the last symbol otherwise
+        let filler = alphabet
+            .index_of('X')
+            .unwrap_or_else(|| alphabet.index_of(*alphabet.symbols().last().unwrap()).unwrap());
+        Ok(HillCipher {
+            key,
+            inverse,
+            alphabet,
+            filler,
+        })
+    }
+
+    /// The symbol used to pad the last block of the message
+    pub fn with_filler(mut self, filler: char) -> Result<Self, CipherError> {
+        self.filler = self
+            .alphabet
+            .index_of(filler)
+            .ok_or(CipherError::SymbolNotInAlphabet(filler))?;
+        Ok(self)
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.key.size()
+    }
+
+    fn prepare_message(&self, message: &str) -> Vec<u32> {
+        let mut message = self.alphabet.to_indices(message);
+        while !message.len().is_multiple_of(self.block_size()) {
+            message.push(self.filler);
+        }
+        message
+    }
+
+    fn multiply_blocks(&self, matrix: &Matrix, message: &[u32]) -> String {
+        let modulus = self.alphabet.modulus() as i64;
+        self.alphabet.from_indices(
+            &message
+                .chunks(self.block_size())
+                .flat_map(|block| {
+                    matrix.multiply_vector(&block.iter().map(|x| *x as i64).collect::<Vec<i64>>(), modulus)
+                })
+                .map(|x| x as u32)
+                .collect::<Vec<u32>>(),
+        )
+    }
+}
+
+impl Cipher for HillCipher {
+    type Key = Matrix;
+
+    fn name(&self) -> &'static str {
+        "Hill"
+    }
+
+    fn key(&self) -> Matrix {
+        self.key.clone()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn preserves_positions(&self) -> bool {
+        true
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&self.multiply_blocks(&self.key, &self.prepare_message(&message)))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.alphabet.to_indices(&encrypted_message);
+        if !encrypted_message.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength {
+                length: encrypted_message.len(),
+                block_size: self.block_size(),
+            });
+        }
+        Ok(self.multiply_blocks(&self.inverse, &encrypted_message))
+    }
+}
+
+#[cfg(test)]
+mod hill_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::modular::Matrix;
+    use crate::polyalphabetic::hill::manipulations::HillCipher;
+
+    fn trigraph_key() -> Matrix {
+        // GYBNQKURP
+        Matrix::new(vec![6, 24, 1, 13, 16, 10, 20, 17, 15]).unwrap()
+    }
+
+    #[test]
+    fn trigraph_encrypt_works() {
+        let cipher = HillCipher::with_alphabet(trigraph_key(), Alphabet::english().one_based(false)).unwrap();
+        assert_eq!(cipher.encrypt("act".to_owned()), "POH".to_owned());
+        assert_eq!(cipher.decrypt("POH".to_owned()), Ok("ACT".to_owned()));
+    }
+
+    #[test]
+    fn same_as_digraph_cipher_for_two_by_two_keys() {
+        let cipher = HillCipher::new(Matrix::new(vec![5, 3, 11, 8]).unwrap()).unwrap();
+        assert_eq!(cipher.encrypt("book".to_owned()), "CLDS".to_owned());
+    }
+
+    #[test]
+    fn last_block_is_padded_with_filler() {
+        let cipher = HillCipher::new(trigraph_key()).unwrap();
+        let encrypted = cipher.encrypt("Meet you".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("MEETYOUXX".to_owned()));
+
+        let cipher = HillCipher::new(trigraph_key()).unwrap().with_filler('q').unwrap();
+        let encrypted = cipher.encrypt("Meet you".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("MEETYOUQQ".to_owned()));
+
+        assert!(HillCipher::new(trigraph_key()).unwrap().with_filler('!').is_err());
+    }
+
+    #[test]
+    fn four_by_four_keys_work() {
+        let key = Matrix::new(vec![1, 2, 3, 4, 0, 1, 4, 5, 5, 6, 0, 7, 8, 9, 10, 0]).unwrap();
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZÆØÅ").unwrap();
+        let cipher = HillCipher::with_alphabet(key, alphabet).unwrap();
+        let encrypted = cipher.encrypt("Rødgrød med fløde".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("RØDGRØDMEDFLØDEX".to_owned()));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(
+            HillCipher::new(Matrix::new(vec![2, 0, 0, 0, 1, 0, 0, 0, 1]).unwrap()).err(),
+            Some(CipherError::NonInvertibleKey { value: 2, modulus: 26 })
+        );
+        assert_eq!(
+            HillCipher::new(trigraph_key()).unwrap().decrypt("POHP".to_owned()),
+            Err(CipherError::InvalidLength { length: 4, block_size: 3 })
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod hacking;
+pub mod manipulations;
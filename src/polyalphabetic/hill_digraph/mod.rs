@@ -1,2 +1,2 @@
-mod hacking;
-mod manipulations;
\ No newline at end of file
+pub mod hacking;
+pub mod manipulations;
@@ -1 +1,6 @@
-mod hill_digraph;
\ No newline at end of file
+pub mod four_square;
+pub mod hill;
+pub mod hill_digraph;
+pub mod playfair;
+pub mod two_square;
+pub mod vigenere;
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, Annealing};
+use crate::error::CipherError;
+use crate::polybius::search::{self, digraphs, square_scorer, SquareSearch};
+use crate::polybius::{merged_alphabet, positions, square_size};
+use crate::polyalphabetic::playfair::manipulations::replace_digraphs;
+use crate::scoring::Scorer;
+
+/// The temperatures of the searches (see `SquareSearch::temperatures`), warmer at the end than
+/// for two squares as the single square gets stuck more often
+const TEMPERATURES: (f64, f64) = (0.035, 0.02);
+
+/// Breaks the Playfair cipher with simulated annealing over the squares, like `SubstitutionHack`:
+/// the square is changed a little at every step (two letters, rows or columns are swapped,
+/// or it is flipped), a better square is always kept, a worse one sometimes, less and less often
+/// while the temperature goes down. Any rotation of the rows or the columns of a square gives
+/// the same cipher, so the found square can differ from the original one in that.
+///
+/// A crib (known plaintext at a known position) helps a lot: before every search the square is
+/// changed until it decrypts the pairs covered by the crib to it, and the search keeps to such squares.
+/// `possible_crib_positions` lists the positions where the crib can be.
+///
+/// Playfair is much harder to break than the substitution cipher: a few hundred letters are needed,
+/// and more restarts or iterations for shorter messages.
+pub struct PlayfairHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+    crib: Option<(String, usize)>,
+}
+
+impl PlayfairHack {
+    /// The 5×5 square with J written as I
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, merged_alphabet('J', 'I').unwrap())
+    }
+
+    /// The alphabet of the square, like `polybius::alphanumeric_alphabet()` for 6×6 squares
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        PlayfairHack {
+            encrypted_message,
+            alphabet: alphabet.one_based(false),
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+            crib: None,
+        }
+    }
+
+    annealing_builders!(5, 200000);
+
+    /// Known plaintext starting at the given letter of the message, as it is in the decrypted
+    /// message (with the fillers)
+    pub fn with_crib(mut self, crib: &str, position: usize) -> Self {
+        self.crib = Some((crib.to_owned(), position));
+        self
+    }
+
+    /// The positions where the crib can be: a letter is never encrypted to itself, a pair of the
+    /// crib can't be a doubled letter and the same pairs (or reversed pairs) are encrypted the same way
+    pub fn possible_crib_positions(&self, crib: &str) -> Result<Vec<usize>, CipherError> {
+        let message = digraphs(&self.encrypted_message, &self.alphabet)?;
+        let crib = search::crib(crib, 0, &message, &self.alphabet)?;
+        Ok((0..=message.len() - crib.len())
+            .filter(|position| Self::fits(&message, &crib, *position))
+            .collect())
+    }
+
+    /// The square found by every search (row by row, so it can be given to `PolybiusSquare::new`)
+    /// with the decrypted message and its score, the best one first
+    pub fn get_ranked_variants(&self) -> Result<Vec<(String, String, f64)>, CipherError> {
+        let message = digraphs(&self.encrypted_message, &self.alphabet)?;
+        let size = square_size(self.alphabet.modulus() as usize)?;
+        let crib = match &self.crib {
+            Some((crib, position)) => Some((search::crib(crib, *position, &message, &self.alphabet)?, *position)),
+            None => None,
+        };
+        let default;
+        let scorer = match &self.scorer {
+            Some(scorer) => scorer.as_ref(),
+            None => {
+                default = square_scorer(&self.alphabet);
+                default.as_ref()
+            }
+        };
+        let search = SquareSearch {
+            message: &message,
+            size,
+            squares: 1,
+            symbols: self.alphabet.symbols(),
+            scorer,
+            crib: crib.as_ref().map(|(crib, position)| (crib.as_slice(), *position)),
+            temperatures: TEMPERATURES,
+            decrypt: &|cells, message| replace_digraphs(cells, &positions(cells), size, message, size - 1),
+        };
+        let symbols = self.alphabet.symbols();
+        Ok(search
+            .run(&self.annealing)
+            .into_iter()
+            .map(|(cells, text, score)| (cells.iter().map(|x| symbols[*x]).collect(), text, score))
+            .collect())
+    }
+
+    pub fn best(&self) -> Result<(String, String, f64), CipherError> {
+        self.get_ranked_variants()?.into_iter().next().ok_or(CipherError::EmptyInput)
+    }
+
+    fn fits(message: &[usize], crib: &[usize], position: usize) -> bool {
+        let mut pairs = HashMap::new();
+        let mut reverse = HashMap::new();
+        let start = position + position % 2;
+        for i in (start..position + crib.len() - 1).step_by(2) {
+            let plain = (crib[i - position], crib[i + 1 - position]);
+            let encrypted = (message[i], message[i + 1]);
+            if plain.0 == plain.1 || plain.0 == encrypted.0 || plain.1 == encrypted.1 {
+                return false;
+            }
+            for (plain, encrypted) in [(plain, encrypted), ((plain.1, plain.0), (encrypted.1, encrypted.0))] {
+                if *pairs.entry(plain).or_insert(encrypted) != encrypted
+                    || *reverse.entry(encrypted).or_insert(plain) != plain
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod playfair_hack_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::{merged_alphabet, PolybiusSquare};
+    use crate::polyalphabetic::playfair::hacking::PlayfairHack;
+    use crate::polyalphabetic::playfair::manipulations::PlayfairCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us";
+
+    /// The whole first sentence, long enough to break Playfair without a crib
+    const LONG_MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way, in short, \
+        the period was so far like the present period, that some of its noisiest authorities insisted \
+        on its being received, for good or for evil, in the superlative degree of comparison only";
+
+    fn cipher() -> PlayfairCipher {
+        // The searches start from squares shuffled with their seed like this one, so they are tried with other seeds
+        PlayfairCipher::with_square(PolybiusSquare::random(4, merged_alphabet('J', 'I').unwrap()).unwrap())
+    }
+
+    #[test]
+    fn best_breaks_random_square() {
+        let encrypted = cipher().encrypt(LONG_MESSAGE.to_owned());
+        let expected = cipher().decrypt(encrypted.clone()).unwrap();
+        let mut broken = 0;
+        for seed in 10..16 {
+            let hack = PlayfairHack::new(encrypted.clone()).with_seed(seed).with_restarts(1).with_iterations(200000);
+            let (key, message, _) = hack.best().unwrap();
+            let found = PlayfairCipher::with_square(PolybiusSquare::new(&key).unwrap());
+            assert_eq!(found.decrypt(encrypted.clone()), Ok(message.clone()));
+            if message == expected {
+                broken += 1;
+            }
+        }
+        // Only a quarter or so of the searches break the square without a crib, even at this length
+        assert!(broken >= 1, "none of the searches broke the square");
+    }
+
+    #[test]
+    fn crib_helps() {
+        let encrypted = cipher().encrypt(MESSAGE.to_owned());
+        let hack = PlayfairHack::new(encrypted.clone());
+        let positions = hack.possible_crib_positions("ITWASTHEBESTOF").unwrap();
+        assert!(positions.contains(&0));
+        assert!(positions.len() < encrypted.len() / 2, "{:?}", positions);
+        for seed in 10..15 {
+            let hack = PlayfairHack::new(encrypted.clone())
+                .with_seed(seed)
+                .with_restarts(3)
+                .with_iterations(30000)
+                .with_crib("ITWASTHEBESTOF", 0);
+            let (_, message, _) = hack.best().unwrap();
+            assert_eq!(message, cipher().decrypt(encrypted.clone()).unwrap(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(PlayfairHack::new("".to_owned()).best(), Err(CipherError::EmptyInput));
+        assert_eq!(
+            PlayfairHack::new("ABC".to_owned()).best(),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+        assert_eq!(
+            PlayfairHack::new("ABCD".to_owned()).with_crib("ABCD", 2).best(),
+            Err(CipherError::CribOutOfMessage { crib_length: 4, position: 2, message_length: 4 })
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::polybius::{filler, PolybiusSquare};
+
+/// The message is split into pairs of letters, which are replaced using a keyed Polybius square:
+/// letters in the same row are replaced by the letters to their right, letters in the same column
+/// by the letters below them, otherwise by the letters in the other corners of their rectangle
+/// (in the same rows). A filler (X, or Q for a doubled X) is put between doubled letters of a pair
+/// and at the end of a message of odd length.
+pub struct PlayfairCipher {
+    square: PolybiusSquare,
+    filler: usize,
+    alternative_filler: usize,
+}
+
+impl PlayfairCipher {
+    /// The 5×5 square of the keyword, J is written as I
+    pub fn new(keyword: &str) -> Result<Self, CipherError> {
+        Ok(Self::with_square(PolybiusSquare::from_keyword(keyword)?))
+    }
+
+    /// Any Polybius square, like the 6×6 `PolybiusSquare::alphanumeric`. The fillers are X and Q if
+    /// they are in the square, otherwise the last symbols of the alphabet
+    pub fn with_square(square: PolybiusSquare) -> Self {
+        let filler = filler(square.alphabet());
+        let alternative_filler = square
+            .alphabet()
+            .symbols()
+            .iter()
+            .position(|s| *s == 'Q')
+            .filter(|x| *x != filler)
+            .unwrap_or(if filler == 0 { 1 } else { filler - 1 });
+        PlayfairCipher {
+            square,
+            filler,
+            alternative_filler,
+        }
+    }
+
+    /// The letter put between doubled letters and the one used when the doubled letter is the filler itself
+    pub fn with_fillers(mut self, filler: char, alternative: char) -> Result<Self, CipherError> {
+        let alphabet = self.square.alphabet();
+        let filler = alphabet.index_of(filler).ok_or(CipherError::SymbolNotInAlphabet(filler))? as usize;
+        let alternative = alphabet
+            .index_of(alternative)
+            .ok_or(CipherError::SymbolNotInAlphabet(alternative))? as usize;
+        if filler == alternative {
+            return Err(CipherError::InvalidKey("the fillers should be different".to_owned()));
+        }
+        self.filler = filler;
+        self.alternative_filler = alternative;
+        Ok(self)
+    }
+
+    pub fn square(&self) -> &PolybiusSquare {
+        &self.square
+    }
+
+    /// Pairs of the letters of the message with the fillers added
+    fn digraphs(&self, message: &str) -> Vec<usize> {
+        let letters = self.square.indices(message);
+        let mut digraphs = Vec::with_capacity(letters.len() + letters.len() / 4 + 1);
+        let mut i = 0;
+        while i < letters.len() {
+            let first = letters[i];
+            let filler = if first == self.filler {
+                self.alternative_filler
+            } else {
+                self.filler
+            };
+            digraphs.push(first);
+            match letters.get(i + 1) {
+                Some(second) if *second != first => {
+                    digraphs.push(*second);
+                    i += 2;
+                }
+                _ => {
+                    digraphs.push(filler);
+                    i += 1;
+                }
+            }
+        }
+        digraphs
+    }
+
+    fn to_string(&self, indices: &[usize]) -> String {
+        indices.iter().map(|x| self.square.alphabet().symbols()[*x]).collect()
+    }
+}
+
+/// Replaces the pairs of symbols (positions in the alphabet) using the square given as its cells
+/// and the cell of every symbol, the shift is 1 to encrypt and `size - 1` to decrypt
+pub(crate) fn replace_digraphs(
+    cells: &[usize],
+    positions: &[usize],
+    size: usize,
+    message: &[usize],
+    shift: usize,
+) -> Vec<usize> {
+    let mut result = Vec::with_capacity(message.len());
+    for pair in message.chunks_exact(2) {
+        let (a, b) = (positions[pair[0]], positions[pair[1]]);
+        let (row_a, column_a, row_b, column_b) = (a / size, a % size, b / size, b % size);
+        let (a, b) = if row_a == row_b {
+            (
+                row_a * size + (column_a + shift) % size,
+                row_b * size + (column_b + shift) % size,
+            )
+        } else if column_a == column_b {
+            (
+                (row_a + shift) % size * size + column_a,
+                (row_b + shift) % size * size + column_b,
+            )
+        } else {
+            (row_a * size + column_b, row_b * size + column_a)
+        };
+        result.push(cells[a]);
+        result.push(cells[b]);
+    }
+    result
+}
+
+impl Cipher for PlayfairCipher {
+    /// The square row by row
+    type Key = String;
+
+    fn name(&self) -> &'static str {
+        "Playfair"
+    }
+
+    fn key(&self) -> String {
+        self.square.key()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        self.square.alphabet()
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let digraphs = self.digraphs(&message);
+        let encrypted = replace_digraphs(self.square.cells(), self.square.positions(), self.square.size(), &digraphs, 1);
+        Grouping::default().format(&self.to_string(&encrypted))
+    }
+
+    /// The fillers stay in the decrypted message
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.square.indices(&encrypted_message);
+        if !encrypted_message.len().is_multiple_of(2) {
+            return Err(CipherError::InvalidLength {
+                length: encrypted_message.len(),
+                block_size: 2,
+            });
+        }
+        let size = self.square.size();
+        let decrypted = replace_digraphs(self.square.cells(), self.square.positions(), size, &encrypted_message, size - 1);
+        Ok(self.to_string(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod playfair_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::PolybiusSquare;
+    use crate::polyalphabetic::playfair::manipulations::PlayfairCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = PlayfairCipher::new("playfair example").unwrap();
+        assert_eq!(
+            cipher.encrypt("Hide the gold in the tree stump".to_owned()),
+            "BMODZ BXDNA BEKUD MUIXM MOUVI F"
+        );
+        assert_eq!(cipher.key(), "PLAYFIREXMBCDGHKNOQSTUVWZ");
+    }
+
+    #[test]
+    fn decrypt_works() {
+        let cipher = PlayfairCipher::new("playfair example").unwrap();
+        assert_eq!(
+            cipher.decrypt("BMODZ BXDNA BEKUD MUIXM MOUVI F".to_owned()),
+            Ok("HIDETHEGOLDINTHETREXESTUMP".to_owned())
+        );
+        assert_eq!(
+            cipher.decrypt("BMO".to_owned()),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+    }
+
+    #[test]
+    fn fillers_work() {
+        let cipher = PlayfairCipher::new("monarchy").unwrap();
+        let encrypted = cipher.encrypt("Jazz exxcess".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("IAZXZEXQXCESSX".to_owned()));
+        let cipher = cipher.with_fillers('z', 'y').unwrap();
+        let encrypted = cipher.encrypt("Jazz exxcess".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("IAZYZEXZXCESSZ".to_owned()));
+        let cipher = PlayfairCipher::new("monarchy").unwrap();
+        assert_eq!(cipher.with_fillers('X', 'X').err(), Some(CipherError::InvalidKey("the fillers should be different".to_owned())));
+    }
+
+    #[test]
+    fn alphanumeric_square_works() {
+        let cipher = PlayfairCipher::with_square(PolybiusSquare::alphanumeric("agent 007").unwrap());
+        let encrypted = cipher.encrypt("Meet at 10:45 at the bridge".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("MEETAT1045ATTHEBRIDGEX".to_owned()));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, Annealing};
+use crate::error::CipherError;
+use crate::polybius::search::{self, digraphs, square_scorer, SquareSearch};
+use crate::polybius::{merged_alphabet, square_size, Squares};
+use crate::polyalphabetic::two_square::manipulations::{replace_digraphs, Orientation};
+use crate::scoring::Scorer;
+
+/// Breaks the two-square cipher with a hill-climb over both keyed squares, the same search as
+/// `PlayfairHack` uses: one of the squares is changed a little at every step and worse squares
+/// are sometimes kept while the temperature goes down.
+///
+/// A crib (known plaintext at a known position) helps a lot, the squares are first changed
+/// until they decrypt it. Two squares need longer messages than Playfair, and even with a crib
+/// only about a third of the searches break them, so there are many restarts.
+pub struct TwoSquareHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    orientation: Orientation,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+    crib: Option<(String, usize)>,
+}
+
+impl TwoSquareHack {
+    /// Vertical 5×5 squares with J written as I
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, merged_alphabet('J', 'I').unwrap())
+    }
+
+    /// The alphabet of the squares, like `polybius::alphanumeric_alphabet()` for 6×6 squares
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        TwoSquareHack {
+            encrypted_message,
+            alphabet: alphabet.one_based(false),
+            orientation: Orientation::default(),
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+            crib: None,
+        }
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    annealing_builders!(15, 100000);
+
+    /// Known plaintext starting at the given letter of the message
+    pub fn with_crib(mut self, crib: &str, position: usize) -> Self {
+        self.crib = Some((crib.to_owned(), position));
+        self
+    }
+
+    /// The squares found by every search (row by row, so they can be given to `PolybiusSquare::new`)
+    /// with the decrypted message and its score, the best one first
+    pub fn get_ranked_variants(&self) -> Result<Vec<(Squares, String, f64)>, CipherError> {
+        let message = digraphs(&self.encrypted_message, &self.alphabet)?;
+        let size = square_size(self.alphabet.modulus() as usize)?;
+        let crib = match &self.crib {
+            Some((crib, position)) => Some((search::crib(crib, *position, &message, &self.alphabet)?, *position)),
+            None => None,
+        };
+        let default;
+        let scorer = match &self.scorer {
+            Some(scorer) => scorer.as_ref(),
+            None => {
+                default = square_scorer(&self.alphabet);
+                default.as_ref()
+            }
+        };
+        let orientation = self.orientation;
+        let search = SquareSearch {
+            message: &message,
+            size,
+            squares: 2,
+            symbols: self.alphabet.symbols(),
+            scorer,
+            crib: crib.as_ref().map(|(crib, position)| (crib.as_slice(), *position)),
+            temperatures: search::TEMPERATURES,
+            decrypt: &|cells, message| replace_digraphs(cells, size, orientation, message, true),
+        };
+        let symbols = self.alphabet.symbols();
+        let to_string = |cells: &[usize]| cells.iter().map(|x| symbols[*x]).collect::<String>();
+        Ok(search
+            .run(&self.annealing)
+            .into_iter()
+            .map(|(cells, text, score)| {
+                let (first, second) = cells.split_at(size * size);
+                ((to_string(first), to_string(second)), text, score)
+            })
+            .collect())
+    }
+
+    pub fn best(&self) -> Result<(Squares, String, f64), CipherError> {
+        self.get_ranked_variants()?.into_iter().next().ok_or(CipherError::EmptyInput)
+    }
+}
+
+#[cfg(test)]
+mod two_square_hack_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::{merged_alphabet, PolybiusSquare};
+    use crate::polyalphabetic::two_square::hacking::TwoSquareHack;
+    use crate::polyalphabetic::two_square::manipulations::{Orientation, TwoSquareCipher};
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way";
+
+    fn cipher() -> TwoSquareCipher {
+        // The searches start from squares shuffled with their seed like these, so they are tried with other seeds
+        let square = |seed| PolybiusSquare::random(seed, merged_alphabet('J', 'I').unwrap()).unwrap();
+        TwoSquareCipher::with_squares(square(4), square(5)).unwrap()
+    }
+
+    #[test]
+    fn crib_breaks_random_squares() {
+        let encrypted = cipher().encrypt(MESSAGE.to_owned());
+        let expected = cipher().decrypt(encrypted.clone()).unwrap();
+        let mut broken = 0;
+        for seed in 10..20 {
+            let hack = TwoSquareHack::new(encrypted.clone())
+                .with_seed(seed)
+                .with_restarts(1)
+                .with_iterations(100000)
+                .with_crib("ITWASTHEBESTOFTIMESITWASTHEWORST", 0);
+            let ((first, second), message, _) = hack.best().unwrap();
+            let found =
+                TwoSquareCipher::with_squares(PolybiusSquare::new(&first).unwrap(), PolybiusSquare::new(&second).unwrap())
+                    .unwrap();
+            assert_eq!(found.decrypt(encrypted.clone()), Ok(message.clone()));
+            if message == expected {
+                broken += 1;
+            }
+        }
+        // About a third of the searches break the squares
+        assert!(broken >= 2, "only {} of the searches broke the squares", broken);
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(TwoSquareHack::new("".to_owned()).best(), Err(CipherError::EmptyInput));
+        assert_eq!(
+            TwoSquareHack::new("ABC".to_owned()).with_orientation(Orientation::Horizontal).best(),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+        assert_eq!(
+            TwoSquareHack::new("ABCD".to_owned()).with_crib("ABCD", 2).best(),
+            Err(CipherError::CribOutOfMessage { crib_length: 4, position: 2, message_length: 4 })
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::polybius::{filler, positions, PolybiusSquare};
+
+/// How the two squares are placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// The first square is on the left of the second one
+    Horizontal,
+    /// The first square is above the second one
+    #[default]
+    Vertical,
+}
+
+/// The first letter of every pair is found in the first square and the second letter in the second
+/// square, they are replaced by the letters in the other corners of their rectangle, the one in the
+/// row of the first letter goes first. When the letters are in the same column of the vertical squares
+/// the pair stays as it is, in the same row of the horizontal squares it is reversed.
+/// A message of odd length gets an X at the end.
+pub struct TwoSquareCipher {
+    first: PolybiusSquare,
+    second: PolybiusSquare,
+    orientation: Orientation,
+}
+
+impl TwoSquareCipher {
+    /// Vertical 5×5 squares of the keywords, J is written as I
+    pub fn new(first_keyword: &str, second_keyword: &str) -> Result<Self, CipherError> {
+        Self::with_squares(
+            PolybiusSquare::from_keyword(first_keyword)?,
+            PolybiusSquare::from_keyword(second_keyword)?,
+        )
+    }
+
+    /// The squares should have the same alphabet
+    pub fn with_squares(first: PolybiusSquare, second: PolybiusSquare) -> Result<Self, CipherError> {
+        if first.alphabet() != second.alphabet() {
+            return Err(CipherError::InvalidKey("the squares have different alphabets".to_owned()));
+        }
+        Ok(TwoSquareCipher {
+            first,
+            second,
+            orientation: Orientation::default(),
+        })
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    fn to_string(&self, indices: &[usize]) -> String {
+        indices.iter().map(|x| self.first.alphabet().symbols()[*x]).collect()
+    }
+
+    fn cells(&self) -> Vec<usize> {
+        [self.first.cells(), self.second.cells()].concat()
+    }
+}
+
+/// Replaces the pairs of symbols (positions in the alphabet) using the squares given as the symbols
+/// of their cells, the first square and then the second one
+pub(crate) fn replace_digraphs(
+    squares: &[usize],
+    size: usize,
+    orientation: Orientation,
+    message: &[usize],
+    decrypt: bool,
+) -> Vec<usize> {
+    let (first, second) = squares.split_at(size * size);
+    let (first_positions, second_positions) = (positions(first), positions(second));
+    // The squares where the letters of the pairs are found and the squares of the letters they are replaced with
+    let (a_positions, b_positions, a_output, b_output) = match (orientation, decrypt) {
+        (Orientation::Vertical, _) => (&first_positions, &second_positions, first, second),
+        (Orientation::Horizontal, false) => (&first_positions, &second_positions, second, first),
+        (Orientation::Horizontal, true) => (&second_positions, &first_positions, first, second),
+    };
+    let mut result = Vec::with_capacity(message.len());
+    for pair in message.chunks_exact(2) {
+        let (a, b) = (a_positions[pair[0]], b_positions[pair[1]]);
+        let (row_a, column_a, row_b, column_b) = (a / size, a % size, b / size, b % size);
+        result.push(a_output[row_a * size + column_b]);
+        result.push(b_output[row_b * size + column_a]);
+    }
+    result
+}
+
+impl Cipher for TwoSquareCipher {
+    /// The squares row by row
+    type Key = (String, String);
+
+    fn name(&self) -> &'static str {
+        "Two-square"
+    }
+
+    fn key(&self) -> (String, String) {
+        (self.first.key(), self.second.key())
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        self.first.alphabet()
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let mut letters = self.first.indices(&message);
+        if !letters.len().is_multiple_of(2) {
+            letters.push(filler(self.first.alphabet()));
+        }
+        let encrypted = replace_digraphs(&self.cells(), self.first.size(), self.orientation, &letters, false);
+        Grouping::default().format(&self.to_string(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.first.indices(&encrypted_message);
+        if !encrypted_message.len().is_multiple_of(2) {
+            return Err(CipherError::InvalidLength {
+                length: encrypted_message.len(),
+                block_size: 2,
+            });
+        }
+        let decrypted = replace_digraphs(&self.cells(), self.first.size(), self.orientation, &encrypted_message, true);
+        Ok(self.to_string(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod two_square_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polybius::PolybiusSquare;
+    use crate::polyalphabetic::two_square::manipulations::{Orientation, TwoSquareCipher};
+
+    // The squares of the examples on Wikipedia leave out Q
+    fn example() -> TwoSquareCipher {
+        let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPRSTUVWXYZ").unwrap();
+        TwoSquareCipher::with_squares(
+            PolybiusSquare::from_keyword_with_alphabet("example", alphabet.clone()).unwrap(),
+            PolybiusSquare::from_keyword_with_alphabet("keyword", alphabet).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn vertical_works() {
+        let cipher = example();
+        let encrypted = cipher.encrypt("Help me Obi Wan Kenobi".to_owned());
+        assert_eq!(encrypted, "HEDLX WSDJY ANHOT KDG");
+        assert_eq!(cipher.decrypt(encrypted), Ok("HELPMEOBIWANKENOBI".to_owned()));
+    }
+
+    #[test]
+    fn horizontal_works() {
+        let cipher = TwoSquareCipher::new("example", "keyword")
+            .unwrap()
+            .with_orientation(Orientation::Horizontal);
+        let encrypted = cipher.encrypt("Help me Obi Wan Kenobi!".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("HELPMEOBIWANKENOBI".to_owned()));
+        // E and K are in the first rows of the squares
+        assert_eq!(cipher.encrypt("ek".to_owned()), "KE");
+        assert_eq!(cipher.encrypt("Odd".to_owned()), cipher.encrypt("oddx".to_owned()));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let cipher = TwoSquareCipher::new("example", "keyword").unwrap();
+        assert_eq!(
+            cipher.decrypt("HED".to_owned()),
+            Err(CipherError::InvalidLength { length: 3, block_size: 2 })
+        );
+        let squares = TwoSquareCipher::with_squares(
+            PolybiusSquare::from_keyword("example").unwrap(),
+            PolybiusSquare::alphanumeric("keyword").unwrap(),
+        );
+        assert_eq!(
+            squares.err(),
+            Some(CipherError::InvalidKey("the squares have different alphabets".to_owned()))
+        );
+    }
+}
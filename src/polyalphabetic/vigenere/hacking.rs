@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::monoalphabetic::additive::caesar::hacking::CaesarHack;
+use crate::scoring::frequencies::{index_of_coincidence, language_index_of_coincidence};
+use crate::scoring::{rank_or_default, Scorer};
+
+/// The length of the repeated sequences searched by the Kasiski examination
+const KASISKI_SEQUENCE_LENGTH: usize = 3;
+
+/// Breaks the Vigenère cipher in two steps: the key length is estimated with the Kasiski examination
+/// and the index of coincidence of the columns of every length, then every column of the message (letters encrypted with the same
+/// key letter) is broken as a Caesar cipher with the chi-squared statistic.
+pub struct VigenereHack {
+    encrypted_message: Vec<u32>,
+    alphabet: Alphabet,
+    max_key_length: usize,
+    scorer: Option<Box<dyn Scorer>>,
+}
+
+impl VigenereHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        let encrypted_message = alphabet.to_indices(&encrypted_message);
+        VigenereHack {
+            max_key_length: (encrypted_message.len() / 2).clamp(1, 20),
+            encrypted_message,
+            alphabet,
+            scorer: None,
+        }
+    }
+
+    /// The longest key length that is checked, 20 by default (or half of the message if it's shorter)
+    pub fn with_max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = max_key_length.max(1);
+        self
+    }
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    /// Kasiski examination: the distances between the repeated sequences of the message are likely
+    /// to be multiples of the key length. Returns the key lengths with the number of the distances
+    /// they divide, the most common first
+    pub fn kasiski_examination(&self) -> Vec<(usize, usize)> {
+        let mut positions: HashMap<&[u32], Vec<usize>> = HashMap::new();
+        for (i, sequence) in self.encrypted_message.windows(KASISKI_SEQUENCE_LENGTH).enumerate() {
+            positions.entry(sequence).or_default().push(i);
+        }
+        let distances = positions
+            .values()
+            .flat_map(|positions| positions.windows(2).map(|pair| pair[1] - pair[0]))
+            .collect::<Vec<usize>>();
+        let mut lengths = (2..=self.max_key_length)
+            .map(|length| (length, distances.iter().filter(|d| d.is_multiple_of(length)).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect::<Vec<(usize, usize)>>();
+        lengths.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        lengths
+    }
+
+    /// Key lengths from the most probable one. The lengths whose columns have the index of coincidence
+    /// closer to the language than to random text (Friedman's test for every length) come first, ordered by the Kasiski examination (so
+    /// the multiples of the key length, which have good columns too, come after it)
+    pub fn get_key_lengths(&self) -> Vec<usize> {
+        let random = 1.0 / self.alphabet.modulus() as f64;
+        let expected = language_index_of_coincidence(&self.alphabet).unwrap_or(random);
+        let kasiski = self.kasiski_examination();
+        let kasiski_count = |length: usize| kasiski.iter().find(|x| x.0 == length).map_or(0, |x| x.1);
+        let mut lengths = (1..=self.max_key_length)
+            .map(|length| {
+                let columns = self.columns(length);
+                let index = columns.iter().filter_map(|c| index_of_coincidence(c)).sum::<f64>() / length as f64;
+                (length, index)
+            })
+            .collect::<Vec<(usize, f64)>>();
+        let is_language = |index: f64| index >= (expected + random) / 2.0;
+        lengths.sort_by(|a, b| match (is_language(a.1), is_language(b.1)) {
+            (true, true) => kasiski_count(b.0).cmp(&kasiski_count(a.0)).then(a.0.cmp(&b.0)),
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            (false, false) => b.1.partial_cmp(&a.1).unwrap(),
+        });
+        lengths.into_iter().map(|x| x.0).collect()
+    }
+
+    /// The key found for every key length with the decrypted message, from the most probable one.
+    /// The keys are reduced to their shortest period, so the same key isn't repeated.
+    /// Empty if the alphabet has no frequencies
+    pub fn get_all_variants(&self) -> Vec<(String, String)> {
+        let mut variants: Vec<(String, String)> = vec![];
+        for length in self.get_key_lengths() {
+            let shifts = match self.find_shifts(length) {
+                Some(shifts) => shifts,
+                None => return vec![],
+            };
+            let shifts = shortest_period(&shifts);
+            let key = shifts.iter().map(|s| self.alphabet.symbols()[*s as usize]).collect::<String>();
+            if variants.iter().any(|(k, _)| *k == key) {
+                continue;
+            }
+            let modulus = self.alphabet.modulus();
+            let message = self
+                .encrypted_message
+                .iter()
+                .zip(shifts.iter().cycle())
+                .map(|(x, shift)| (x + modulus - shift) % modulus)
+                .collect::<Vec<u32>>();
+            variants.push((key, self.alphabet.from_indices(&message)));
+        }
+        variants
+    }
+
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(String, String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<(String, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+
+    pub fn print_all_variants(&self) {
+        self.get_all_variants()
+            .iter()
+            .for_each(|(key, message)| println!("{}: {}", key, message));
+    }
+
+    /// The most probable Caesar shift of every column
+    fn find_shifts(&self, length: usize) -> Option<Vec<u32>> {
+        self.columns(length)
+            .into_iter()
+            .map(|column| {
+                CaesarHack::with_alphabet(self.alphabet.from_indices(&column), self.alphabet.clone())
+                    .get_variants_by_frequencies()
+                    .first()
+                    .map(|(shift, _)| *shift)
+            })
+            .collect()
+    }
+
+    /// The letters encrypted with the same letter of the key
+    fn columns(&self, length: usize) -> Vec<Vec<u32>> {
+        (0..length)
+            .map(|i| self.encrypted_message.iter().skip(i).step_by(length).cloned().collect())
+            .collect()
+    }
+}
+
+fn shortest_period(shifts: &[u32]) -> Vec<u32> {
+    let period = (1..=shifts.len())
+        .find(|period| {
+            shifts.len().is_multiple_of(*period) && shifts.iter().zip(shifts.iter().skip(*period)).all(|(a, b)| a == b)
+        })
+        .unwrap_or(shifts.len());
+    shifts[..period].to_vec()
+}
+
+#[cfg(test)]
+mod vigenere_hack_test {
+    use crate::cipher::Cipher;
+    use crate::polyalphabetic::vigenere::hacking::VigenereHack;
+    use crate::polyalphabetic::vigenere::manipulations::VigenereCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us";
+
+    #[test]
+    fn kasiski_examination_finds_key_length() {
+        let encrypted = VigenereCipher::new("dickens").unwrap().encrypt(MESSAGE.to_owned());
+        let hack = VigenereHack::new(encrypted);
+        assert!(hack.kasiski_examination().iter().take(2).any(|(length, _)| *length == 7));
+        assert_eq!(hack.get_key_lengths()[0], 7);
+    }
+
+    #[test]
+    fn get_all_variants_finds_the_key() {
+        let encrypted = VigenereCipher::new("dickens").unwrap().encrypt(MESSAGE.to_owned());
+        let variants = VigenereHack::new(encrypted).get_all_variants();
+        let expected = VigenereCipher::new("a").unwrap().decrypt(MESSAGE.to_owned()).unwrap();
+        assert_eq!(variants[0], ("DICKENS".to_owned(), expected));
+    }
+
+    #[test]
+    fn best_finds_the_key() {
+        let encrypted = VigenereCipher::new("cipher").unwrap().encrypt(MESSAGE.to_owned());
+        assert_eq!(VigenereHack::new(encrypted).best().unwrap().0, "CIPHER".to_owned());
+    }
+}
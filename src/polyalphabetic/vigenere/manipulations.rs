@@ -0,0 +1,106 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+
+/// Every letter of the message is shifted by the letter of the keyword in the same position,
+/// the keyword is repeated along the message. A shifts by 0, B by 1 and so on.
+pub struct VigenereCipher {
+    keyword: String,
+    shifts: Vec<u32>,
+    alphabet: Alphabet,
+}
+
+impl VigenereCipher {
+    pub fn new(keyword: &str) -> Result<Self, CipherError> {
+        Self::with_alphabet(keyword, Alphabet::english())
+    }
+
+    pub fn with_alphabet(keyword: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let modulus = alphabet.modulus();
+        // The first symbol of the alphabet doesn't shift, even if the alphabet is one-based
+        let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+        let shifts = alphabet
+            .to_indices_strict(keyword)?
+            .into_iter()
+            .map(|x| (x + modulus - first) % modulus)
+            .collect::<Vec<u32>>();
+        if shifts.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        Ok(VigenereCipher {
+            keyword: alphabet.normalize(keyword),
+            shifts,
+            alphabet,
+        })
+    }
+
+    fn manipulate(&self, message: &str, decrypt: bool) -> String {
+        let modulus = self.alphabet.modulus();
+        self.alphabet.from_indices(
+            &self
+                .alphabet
+                .to_indices(message)
+                .into_iter()
+                .zip(self.shifts.iter().cycle())
+                .map(|(x, shift)| {
+                    let shift = if decrypt { modulus - shift } else { *shift };
+                    (x + shift) % modulus
+                })
+                .collect::<Vec<u32>>(),
+        )
+    }
+}
+
+impl Cipher for VigenereCipher {
+    type Key = String;
+
+    fn name(&self) -> &'static str {
+        "Vigenère"
+    }
+
+    fn key(&self) -> String {
+        self.keyword.clone()
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        Grouping::default().format(&self.manipulate(&message, false))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        Ok(self.manipulate(&encrypted_message, true))
+    }
+}
+
+#[cfg(test)]
+mod vigenere_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::polyalphabetic::vigenere::manipulations::VigenereCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = VigenereCipher::new("lemon").unwrap();
+        assert_eq!(cipher.key(), "LEMON".to_owned());
+        assert_eq!(cipher.encrypt("Attack at dawn".to_owned()), "LXFOP VEFRN HR".to_owned());
+        assert_eq!(cipher.decrypt("LXFOP VEFRN HR".to_owned()), Ok("ATTACKATDAWN".to_owned()));
+    }
+
+    #[test]
+    fn works_with_other_alphabets() {
+        let cipher = VigenereCipher::with_alphabet("ключ", Alphabet::russian()).unwrap();
+        let encrypted = cipher.encrypt("Шифр Виженера".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("ШИФРВИЖЕНЕРА".to_owned()));
+    }
+
+    #[test]
+    fn invalid_keywords_are_rejected() {
+        assert_eq!(VigenereCipher::new("").err(), Some(CipherError::EmptyInput));
+        assert_eq!(VigenereCipher::new("key1").err(), Some(CipherError::SymbolNotInAlphabet('1')));
+    }
+}
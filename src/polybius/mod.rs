@@ -0,0 +1,249 @@
+//! Polybius squares, the keyed grids of the digraphic and fractionating ciphers.
+
+pub(crate) mod search;
+
+use std::fmt;
+
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+use crate::random::Random;
+
+/// Two squares row by row, the key of the ciphers with two keyed squares
+pub type Squares = (String, String);
+
+/// The symbols of an alphabet written into a square grid row by row. The alphabet has to have
+/// a square number of symbols, so English loses a letter in the 5×5 square (J is written as I
+/// by default) and the 6×6 square has the letters and the digits.
+///
+/// ```
+/// use cryptology_for_beginners::polybius::PolybiusSquare;
+///
+/// let square = PolybiusSquare::from_keyword("playfair example").unwrap();
+/// assert_eq!(square.key(), "PLAYFIREXMBCDGHKNOQSTUVWZ");
+/// assert_eq!(square.position('j'), Some((1, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolybiusSquare {
+    size: usize,
+    // Positions of the symbols in the alphabet, row by row
+    cells: Vec<usize>,
+    // The cell of every symbol of the alphabet
+    positions: Vec<usize>,
+    alphabet: Alphabet,
+}
+
+impl PolybiusSquare {
+    /// The 25 letters of the 5×5 square row by row, J is written as I
+    pub fn new(square: &str) -> Result<Self, CipherError> {
+        Self::with_alphabet(square, merged_alphabet('J', 'I')?)
+    }
+
+    /// The square is a permutation of the alphabet, written row by row
+    pub fn with_alphabet(square: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let alphabet = alphabet.one_based(false);
+        let cells = alphabet.to_indices_strict(square)?;
+        if cells.len() != alphabet.modulus() as usize {
+            return Err(CipherError::InvalidKey(format!(
+                "the square has {} symbols instead of {}",
+                cells.len(),
+                alphabet.modulus()
+            )));
+        }
+        if let Some(i) = (1..cells.len()).find(|i| cells[..*i].contains(&cells[*i])) {
+            return Err(CipherError::InvalidKey(format!(
+                "{} is repeated in the square",
+                alphabet.symbol_at(cells[i])
+            )));
+        }
+        Self::from_cells(cells.into_iter().map(|x| x as usize).collect(), alphabet)
+    }
+
+    /// The 5×5 square of the keyword (its letters without repetitions, then the remaining letters
+    /// of the alphabet), J is written as I
+    pub fn from_keyword(keyword: &str) -> Result<Self, CipherError> {
+        Self::from_keyword_with_alphabet(keyword, merged_alphabet('J', 'I')?)
+    }
+
+    /// The 6×6 square of the keyword with the letters and the digits
+    pub fn alphanumeric(keyword: &str) -> Result<Self, CipherError> {
+        Self::from_keyword_with_alphabet(keyword, alphanumeric_alphabet())
+    }
+
+    pub fn from_keyword_with_alphabet(keyword: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let alphabet = alphabet.one_based(false);
+        let mut cells = vec![];
+        for x in alphabet.to_indices_strict(keyword)?.into_iter().map(|x| x as usize) {
+            if !cells.contains(&x) {
+                cells.push(x);
+            }
+        }
+        for x in 0..alphabet.modulus() as usize {
+            if !cells.contains(&x) {
+                cells.push(x);
+            }
+        }
+        Self::from_cells(cells, alphabet)
+    }
+
+    /// A random square of the alphabet, the same seed gives the same square
+    pub fn random(seed: u64, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let mut cells = (0..alphabet.modulus() as usize).collect::<Vec<usize>>();
+        Random::new(seed).shuffle(&mut cells);
+        Self::from_cells(cells, alphabet.one_based(false))
+    }
+
+    pub(crate) fn from_cells(cells: Vec<usize>, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let size = square_size(cells.len())?;
+        Ok(PolybiusSquare {
+            size,
+            positions: positions(&cells),
+            cells,
+            alphabet,
+        })
+    }
+
+    /// The number of the rows and the columns
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// The symbols row by row
+    pub fn key(&self) -> String {
+        self.cells.iter().map(|x| self.alphabet.symbols()[*x]).collect()
+    }
+
+    /// Zero-based row and column of the symbol, merged letters are found in the cell of their pair
+    pub fn position(&self, symbol: char) -> Option<(usize, usize)> {
+        let cell = self.positions[self.alphabet.index_of(symbol)? as usize];
+        Some((cell / self.size, cell % self.size))
+    }
+
+    pub fn symbol_at(&self, row: usize, column: usize) -> char {
+        self.alphabet.symbols()[self.cells[row * self.size + column]]
+    }
+
+    /// Positions of the symbols of the message in the alphabet, everything else is skipped
+    pub(crate) fn indices(&self, message: &str) -> Vec<usize> {
+        self.alphabet.to_indices(message).into_iter().map(|x| x as usize).collect()
+    }
+
+    pub(crate) fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+
+    /// The cell of every symbol of the alphabet
+    pub(crate) fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+/// The rows of the square with the symbols separated by spaces
+impl fmt::Display for PolybiusSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.size {
+            let symbols = (0..self.size)
+                .map(|column| self.symbol_at(row, column).to_string())
+                .collect::<Vec<String>>();
+            if row > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", symbols.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The English letters without `merged`, which is read as `into`, like J and I in the 5×5 square
+pub fn merged_alphabet(merged: char, into: char) -> Result<Alphabet, CipherError> {
+    let english = Alphabet::english();
+    let (merged, into) = match (english.index_of(merged), english.index_of(into)) {
+        (Some(merged), Some(into)) if merged != into => (english.symbol_at(merged), english.symbol_at(into)),
+        _ => {
+            return Err(CipherError::InvalidAlphabet(format!(
+                "{} can't be merged into {}",
+                merged, into
+            )))
+        }
+    };
+    let symbols = english.symbols().iter().filter(|s| **s != merged).collect::<String>();
+    Ok(Alphabet::new(&symbols)?.with_equivalents(&[(merged, into), (merged.to_ascii_lowercase(), into)]))
+}
+
+/// The English letters and the digits of the 6×6 square
+pub fn alphanumeric_alphabet() -> Alphabet {
+    Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap()
+}
+
+/// The number of the rows of the square with the given number of symbols
+pub(crate) fn square_size(symbols: usize) -> Result<usize, CipherError> {
+    let size = (symbols as f64).sqrt().round() as usize;
+    if size < 2 || size * size != symbols {
+        return Err(CipherError::InvalidAlphabet(format!("{} symbols can't fill a square", symbols)));
+    }
+    Ok(size)
+}
+
+/// The cell of every symbol of the square given as the symbols of its cells
+pub(crate) fn positions(cells: &[usize]) -> Vec<usize> {
+    let mut positions = vec![0; cells.len()];
+    for (cell, x) in cells.iter().enumerate() {
+        positions[*x] = cell;
+    }
+    positions
+}
+
+/// The symbol added to complete the last pair of a message: X, or the last symbol of the alphabet without X
+pub(crate) fn filler(alphabet: &Alphabet) -> usize {
+    let symbols = alphabet.symbols();
+    symbols.iter().position(|s| *s == 'X').unwrap_or(symbols.len() - 1)
+}
+
+#[cfg(test)]
+mod polybius_test {
+    use crate::alphabet::Alphabet;
+    use crate::error::CipherError;
+    use crate::polybius::{merged_alphabet, PolybiusSquare};
+
+    #[test]
+    fn keyword_square_works() {
+        let square = PolybiusSquare::from_keyword("Playfair example").unwrap();
+        assert_eq!(square.to_string(), "P L A Y F\nI R E X M\nB C D G H\nK N O Q S\nT U V W Z");
+        assert_eq!(square.position('X'), Some((1, 3)));
+        assert_eq!(square.position('J'), square.position('i'));
+        assert_eq!(square.position('?'), None);
+        assert_eq!(square.symbol_at(4, 4), 'Z');
+        assert_eq!(PolybiusSquare::new(&square.key()), Ok(square));
+    }
+
+    #[test]
+    fn other_squares_work() {
+        let square = PolybiusSquare::alphanumeric("agent 007").unwrap();
+        assert_eq!((square.size(), &square.key()[..8]), (6, "AGENT07B"));
+        let square = PolybiusSquare::from_keyword_with_alphabet("quick", merged_alphabet('Q', 'K').unwrap()).unwrap();
+        assert_eq!(&square.key()[..5], "KUICA");
+        let square = PolybiusSquare::random(3, merged_alphabet('J', 'I').unwrap()).unwrap();
+        assert_eq!(PolybiusSquare::new(&square.key()), Ok(square));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(
+            PolybiusSquare::new("ABCDE"),
+            Err(CipherError::InvalidKey("the square has 5 symbols instead of 25".to_owned()))
+        );
+        assert_eq!(
+            PolybiusSquare::new("AACDEFGHIKLMNOPQRSTUVWXYZ"),
+            Err(CipherError::InvalidKey("A is repeated in the square".to_owned()))
+        );
+        assert_eq!(
+            PolybiusSquare::from_keyword_with_alphabet("", Alphabet::english()),
+            Err(CipherError::InvalidAlphabet("26 symbols can't fill a square".to_owned()))
+        );
+        assert!(merged_alphabet('J', 'J').is_err());
+        assert!(merged_alphabet('J', '1').is_err());
+    }
+}
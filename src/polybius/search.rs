@@ -0,0 +1,254 @@
+//! The search for the keyed squares of the digraphic ciphers, shared by their hacks.
+
+use crate::alphabet::Alphabet;
+use crate::annealing::{Annealing, Cooling};
+use crate::error::CipherError;
+use crate::random::Random;
+use crate::scoring::{default_scorer, Scorer};
+
+/// The temperatures of the searches over two squares, see `SquareSearch::temperatures`
+pub(crate) const TEMPERATURES: (f64, f64) = (0.04, 0.012);
+/// What every letter of the crib that the squares don't give costs, in the scaled score
+const CRIB_PENALTY: f64 = 20.0;
+/// Steps of fitting the squares to the crib before every search
+const CRIB_STEPS: usize = 100000;
+
+/// Decrypts the message with the squares, given as the symbols of their cells one square after another
+pub(crate) type Decrypt<'a> = dyn Fn(&[usize], &[usize]) -> Vec<usize> + 'a;
+
+/// Simulated annealing over the squares of a digraphic cipher, one of the squares is changed a little
+/// at every step. With a crib the squares are first changed until they decrypt the crib, and the search keeps to such squares.
+pub(crate) struct SquareSearch<'a> {
+    /// Positions of the letters of the encrypted message in the alphabet
+    pub(crate) message: &'a [usize],
+    pub(crate) size: usize,
+    /// How many keyed squares the cipher has
+    pub(crate) squares: usize,
+    pub(crate) symbols: &'a [char],
+    pub(crate) scorer: &'a dyn Scorer,
+    /// The crib and its position in the message
+    pub(crate) crib: Option<(&'a [usize], usize)>,
+    /// The temperatures at the start and at the end of every search, per letter of the message
+    /// as the fitness grows with its length
+    pub(crate) temperatures: (f64, f64),
+    pub(crate) decrypt: &'a Decrypt<'a>,
+}
+
+impl SquareSearch<'_> {
+    /// The squares found by every search from random squares with the decrypted message and its score,
+    /// the best one first
+    pub(crate) fn run(&self, annealing: &Annealing) -> Vec<(Vec<usize>, String, f64)> {
+        let cells = self.size * self.size;
+        let mut random = Random::new(annealing.seed);
+        let mut variants: Vec<(Vec<usize>, String, f64)> = vec![];
+        let length = self.message.len() as f64;
+        let cooling = Cooling::Exponential(self.temperatures.0 * length, self.temperatures.1 * length);
+        for _ in 0..annealing.restarts {
+            let mut squares = vec![];
+            for _ in 0..self.squares {
+                let mut square = (0..cells).collect::<Vec<usize>>();
+                random.shuffle(&mut square);
+                squares.extend(square);
+            }
+            let squares = self.fit_crib(squares, &mut random);
+            let (squares, _) = annealing.anneal(
+                squares,
+                cooling,
+                &mut random,
+                |squares| self.fitness(squares),
+                |squares, random| {
+                    self.mutate(squares, random);
+                    true
+                },
+            );
+            let text = self.to_text(&(self.decrypt)(&squares, self.message));
+            let score = self.scorer.score(&text);
+            if !variants.iter().any(|v| v.0 == squares) {
+                variants.push((squares, text, score));
+            }
+        }
+        variants.sort_by(|a, b| b.2.total_cmp(&a.2));
+        variants
+    }
+
+    /// Changes the squares until they decrypt the pairs covered by the crib to it (or gives up),
+    /// that is fast as only these pairs are decrypted
+    fn fit_crib(&self, mut squares: Vec<usize>, random: &mut Random) -> Vec<usize> {
+        let mut missed = self.missed(&squares);
+        for _ in 0..CRIB_STEPS {
+            if missed == 0 {
+                break;
+            }
+            let previous = squares.clone();
+            self.mutate(&mut squares, random);
+            let new_missed = self.missed(&squares);
+            if new_missed <= missed {
+                missed = new_missed;
+            } else {
+                squares = previous;
+            }
+        }
+        squares
+    }
+
+    /// The score scaled to the length of the message, minus the penalty for the missed letters of the crib
+    fn fitness(&self, squares: &[usize]) -> f64 {
+        let text = self.to_text(&(self.decrypt)(squares, self.message));
+        self.scorer.score(&text) * self.message.len() as f64 - CRIB_PENALTY * self.missed(squares) as f64
+    }
+
+    /// The letters of the crib the squares don't decrypt to
+    fn missed(&self, squares: &[usize]) -> usize {
+        match self.crib {
+            Some((crib, position)) => {
+                let start = position - position % 2;
+                let end = (position + crib.len()).div_ceil(2) * 2;
+                let decrypted = (self.decrypt)(squares, &self.message[start..end]);
+                crib.iter()
+                    .zip(&decrypted[position - start..])
+                    .filter(|(expected, found)| expected != found)
+                    .count()
+            }
+            None => 0,
+        }
+    }
+
+    /// One of the squares is changed
+    fn mutate(&self, squares: &mut [usize], random: &mut Random) {
+        let cells = self.size * self.size;
+        let square = if self.squares > 1 { random.below(self.squares) } else { 0 };
+        mutate(&mut squares[square * cells..(square + 1) * cells], self.size, random);
+    }
+
+    fn to_text(&self, indices: &[usize]) -> String {
+        indices.iter().map(|x| self.symbols[*x]).collect()
+    }
+}
+
+/// Positions of the letters of the encrypted message in the alphabet, there should be pairs of them
+pub(crate) fn digraphs(message: &str, alphabet: &Alphabet) -> Result<Vec<usize>, CipherError> {
+    let message = alphabet
+        .to_indices_strict(message)?
+        .into_iter()
+        .map(|x| x as usize)
+        .collect::<Vec<usize>>();
+    if message.is_empty() {
+        return Err(CipherError::EmptyInput);
+    }
+    if !message.len().is_multiple_of(2) {
+        return Err(CipherError::InvalidLength {
+            length: message.len(),
+            block_size: 2,
+        });
+    }
+    Ok(message)
+}
+
+/// Positions of the letters of the crib in the alphabet, if it fits into the message at the position
+pub(crate) fn crib(crib: &str, position: usize, message: &[usize], alphabet: &Alphabet) -> Result<Vec<usize>, CipherError> {
+    let crib = alphabet
+        .to_indices_strict(crib)?
+        .into_iter()
+        .map(|x| x as usize)
+        .collect::<Vec<usize>>();
+    if crib.is_empty() {
+        return Err(CipherError::EmptyInput);
+    }
+    if crib.len() > message.len() {
+        return Err(CipherError::CribTooLong {
+            crib_length: crib.len(),
+            message_length: message.len(),
+        });
+    }
+    if position + crib.len() > message.len() {
+        return Err(CipherError::CribOutOfMessage {
+            crib_length: crib.len(),
+            position,
+            message_length: message.len(),
+        });
+    }
+    Ok(crib)
+}
+
+/// Quadgrams if the square is English letters (and digits), otherwise the default scorer of the alphabet
+pub(crate) fn square_scorer(alphabet: &Alphabet) -> Box<dyn Scorer> {
+    let english = Alphabet::english();
+    if alphabet.symbols().iter().all(|s| s.is_ascii_digit() || english.symbols().contains(s)) {
+        default_scorer(&english)
+    } else {
+        default_scorer(alphabet)
+    }
+}
+
+/// Changes the square a little: mostly two cells are swapped, sometimes two rows
+/// or two columns, or the square is flipped, which keeps most of the neighbours of the symbols
+fn mutate(cells: &mut [usize], size: usize, random: &mut Random) {
+    match random.below(50) {
+        0 => {
+            let (a, b) = (random.below(size), random.below(size));
+            for column in 0..size {
+                cells.swap(a * size + column, b * size + column);
+            }
+        }
+        1 => {
+            let (a, b) = (random.below(size), random.below(size));
+            for row in 0..size {
+                cells.swap(row * size + a, row * size + b);
+            }
+        }
+        2 => {
+            for row in 0..size / 2 {
+                for column in 0..size {
+                    cells.swap(row * size + column, (size - 1 - row) * size + column);
+                }
+            }
+        }
+        3 => {
+            for row in 0..size {
+                cells[row * size..(row + 1) * size].reverse();
+            }
+        }
+        _ => {
+            let (a, b) = (random.below(cells.len()), random.below(cells.len()));
+            cells.swap(a, b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod search_test {
+    use crate::alphabet::Alphabet;
+    use crate::error::CipherError;
+    use crate::polybius::search::{crib, digraphs, mutate};
+    use crate::random::Random;
+
+    #[test]
+    fn mutate_keeps_the_symbols() {
+        let mut cells = (0..25).collect::<Vec<usize>>();
+        let mut random = Random::new(1);
+        for _ in 0..100 {
+            mutate(&mut cells, 5, &mut random);
+        }
+        assert_ne!(cells, (0..25).collect::<Vec<usize>>());
+        cells.sort();
+        assert_eq!(cells, (0..25).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn messages_and_cribs_are_checked() {
+        let alphabet = Alphabet::new("ABCD").unwrap();
+        assert_eq!(digraphs("ab cd", &alphabet), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(digraphs("", &alphabet), Err(CipherError::EmptyInput));
+        assert_eq!(digraphs("ABC", &alphabet), Err(CipherError::InvalidLength { length: 3, block_size: 2 }));
+        assert_eq!(digraphs("ABE", &alphabet), Err(CipherError::SymbolNotInAlphabet('E')));
+        assert_eq!(crib("bc", 1, &[0, 1, 2, 3], &alphabet), Ok(vec![1, 2]));
+        assert_eq!(
+            crib("bcd", 2, &[0, 1, 2, 3], &alphabet),
+            Err(CipherError::CribOutOfMessage { crib_length: 3, position: 2, message_length: 4 })
+        );
+        assert_eq!(
+            crib("abcda", 0, &[0, 1, 2, 3], &alphabet),
+            Err(CipherError::CribTooLong { crib_length: 5, message_length: 4 })
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! A small seeded pseudo-random generator (SplitMix64), so random keys and randomized
+//! attacks can be reproduced from their seed.
+
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in 0..bound
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A number in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_test {
+    use crate::random::Random;
+
+    #[test]
+    fn same_seed_gives_same_numbers() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+        assert_eq!(
+            (0..10).map(|_| first.next_u64()).collect::<Vec<u64>>(),
+            (0..10).map(|_| second.next_u64()).collect::<Vec<u64>>()
+        );
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn shuffle_keeps_values() {
+        let mut values = (0..26).collect::<Vec<u32>>();
+        Random::new(7).shuffle(&mut values);
+        assert_ne!(values, (0..26).collect::<Vec<u32>>());
+        values.sort();
+        assert_eq!(values, (0..26).collect::<Vec<u32>>());
+        let mut random = Random::new(3);
+        assert!((0..100).map(|_| random.next_f64()).all(|x| (0.0..1.0).contains(&x)));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use crate::scoring::Scorer;
+
+const ENGLISH_WORDS: &str = include_str!("../../data/english_words.txt");
+
+/// Scores with the part of the message (from 0 to 1) covered by the words of the dictionary.
+/// The message has no spaces, so the words are found with dynamic programming:
+/// the best coverage of every prefix is the best of the shorter prefixes followed by a word.
+pub struct DictionaryScorer {
+    words: HashSet<String>,
+    longest_word: usize,
+}
+
+impl DictionaryScorer {
+    /// A few thousand common English words
+    pub fn english() -> Self {
+        Self::new(ENGLISH_WORDS.lines())
+    }
+
+    /// One-letter words are skipped, as they would match almost any text
+    pub fn new<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let words = words
+            .into_iter()
+            .map(|word| word.trim().to_uppercase())
+            .filter(|word| word.chars().count() > 1)
+            .collect::<HashSet<String>>();
+        DictionaryScorer {
+            longest_word: words.iter().map(|word| word.chars().count()).max().unwrap_or(0),
+            words,
+        }
+    }
+}
+
+impl Scorer for DictionaryScorer {
+    fn score(&self, message: &str) -> f64 {
+        let letters = message
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(|c| c.to_uppercase())
+            .collect::<Vec<char>>();
+        if letters.is_empty() {
+            return 0.0;
+        }
+        let mut covered = vec![0; letters.len() + 1];
+        for end in 1..=letters.len() {
+            covered[end] = covered[end - 1];
+            for start in end.saturating_sub(self.longest_word)..end - 1 {
+                if self.words.contains(&letters[start..end].iter().collect::<String>()) {
+                    covered[end] = covered[end].max(covered[start] + end - start);
+                }
+            }
+        }
+        covered[letters.len()] as f64 / letters.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod dictionary_test {
+    use crate::scoring::dictionary::DictionaryScorer;
+    use crate::scoring::Scorer;
+
+    #[test]
+    fn coverage_works() {
+        let scorer = DictionaryScorer::new(["meet", "me", "at", "the", "mall", "a"]);
+        assert_eq!(scorer.score("MEETMEATTHEMALL"), 1.0);
+        assert_eq!(scorer.score("MEETXXXX"), 0.5);
+        assert_eq!(scorer.score("AAAA"), 0.0);
+        assert_eq!(scorer.score(""), 0.0);
+    }
+
+    #[test]
+    fn english_words_are_found() {
+        let scorer = DictionaryScorer::english();
+        assert!(scorer.score("IMHUNGRYLETSGETAPIZZA") > 0.7);
+        assert!(scorer.score("QUPCVOZGTMBAOMBIXQHHI") < 0.3);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+use crate::scoring::Scorer;
+
+/// The chi-squared statistic of the letter counts of the message against the expected counts from
+/// the frequencies of the alphabet, the lower the closer. None if the alphabet has no frequencies
+pub fn chi_squared(message: &[u32], alphabet: &Alphabet) -> Option<f64> {
+    let frequencies = alphabet.frequencies()?;
+    let total = frequencies.iter().sum::<f64>();
+    let modulus = alphabet.modulus();
+    // Frequencies are in the order of the symbols, which differs from the indices for one-based alphabets
+    let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+    let mut counts = vec![0; modulus as usize];
+    for x in message {
+        counts[((x + modulus - first) % modulus) as usize] += 1;
+    }
+    Some(
+        counts
+            .iter()
+            .zip(frequencies)
+            .filter(|(_, frequency)| **frequency > 0.0)
+            .map(|(count, frequency)| {
+                let expected = message.len() as f64 * frequency / total;
+                (*count as f64 - expected).powi(2) / expected
+            })
+            .sum(),
+    )
+}
+
+/// Scores with the negated chi-squared statistic against the letter frequencies of the alphabet
+pub struct ChiSquaredScorer {
+    alphabet: Alphabet,
+}
+
+impl ChiSquaredScorer {
+    pub fn new(alphabet: Alphabet) -> Result<Self, CipherError> {
+        if alphabet.frequencies().is_none() {
+            return Err(CipherError::InvalidAlphabet(
+                "the letter frequencies are needed for the chi-squared statistic".to_owned(),
+            ));
+        }
+        Ok(ChiSquaredScorer { alphabet })
+    }
+
+    pub fn english() -> Self {
+        Self::new(Alphabet::english()).unwrap()
+    }
+}
+
+impl Scorer for ChiSquaredScorer {
+    fn score(&self, message: &str) -> f64 {
+        -chi_squared(&self.alphabet.to_indices(message), &self.alphabet).unwrap()
+    }
+}
+
+/// The probability that two random letters of the message are the same,
+/// None if the message has less than 2 letters
+pub fn index_of_coincidence(message: &[u32]) -> Option<f64> {
+    if message.len() < 2 {
+        return None;
+    }
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for x in message {
+        *counts.entry(*x).or_default() += 1;
+    }
+    let n = message.len();
+    Some(counts.values().map(|c| c * (c - 1)).sum::<usize>() as f64 / (n * (n - 1)) as f64)
+}
+
+/// The index of coincidence of a long text of the language, from the frequencies of the alphabet
+pub fn language_index_of_coincidence(alphabet: &Alphabet) -> Option<f64> {
+    let frequencies = alphabet.frequencies()?;
+    let total = frequencies.iter().sum::<f64>();
+    Some(frequencies.iter().map(|f| (f / total).powi(2)).sum())
+}
+
+/// Scores with the negated distance of the index of coincidence of the message from the language.
+/// It doesn't change when the letters are substituted, so it tells monoalphabetic ciphers
+/// (and transpositions) from polyalphabetic ones rather than ranking the keys of one cipher
+pub struct IndexOfCoincidenceScorer {
+    alphabet: Alphabet,
+    expected: f64,
+}
+
+impl IndexOfCoincidenceScorer {
+    pub fn new(alphabet: Alphabet) -> Result<Self, CipherError> {
+        let expected = language_index_of_coincidence(&alphabet).ok_or_else(|| {
+            CipherError::InvalidAlphabet("the letter frequencies are needed for the index of coincidence".to_owned())
+        })?;
+        Ok(IndexOfCoincidenceScorer { alphabet, expected })
+    }
+
+    pub fn english() -> Self {
+        Self::new(Alphabet::english()).unwrap()
+    }
+}
+
+impl Scorer for IndexOfCoincidenceScorer {
+    fn score(&self, message: &str) -> f64 {
+        let measured = index_of_coincidence(&self.alphabet.to_indices(message)).unwrap_or(0.0);
+        -(measured - self.expected).abs()
+    }
+}
+
+#[cfg(test)]
+mod frequencies_test {
+    use crate::alphabet::Alphabet;
+    use crate::scoring::frequencies::{
+        chi_squared, index_of_coincidence, language_index_of_coincidence, ChiSquaredScorer, IndexOfCoincidenceScorer,
+    };
+    use crate::scoring::Scorer;
+
+    #[test]
+    fn chi_squared_works() {
+        let alphabet = Alphabet::new("AB").unwrap().with_frequencies(vec![75.0, 25.0]).unwrap();
+        assert_eq!(chi_squared(&alphabet.to_indices("AAAB"), &alphabet), Some(0.0));
+        // (2 - 3)^2 / 3 + (2 - 1)^2 / 1
+        assert_eq!(chi_squared(&alphabet.to_indices("AABB"), &alphabet), Some(4.0 / 3.0));
+        assert_eq!(chi_squared(&[0], &Alphabet::new("AB").unwrap()), None);
+    }
+
+    #[test]
+    fn scorer_prefers_english() {
+        let scorer = ChiSquaredScorer::english();
+        assert!(scorer.score("Defend the east wall of the castle") > scorer.score("GHIHQG WKH HDVW ZDOO RI WKH FDVWOH"));
+        assert!(ChiSquaredScorer::new(Alphabet::new("AB").unwrap()).is_err());
+    }
+
+    #[test]
+    fn index_of_coincidence_works() {
+        assert_eq!(index_of_coincidence(&[1, 1, 2, 2]), Some(1.0 / 3.0));
+        assert_eq!(index_of_coincidence(&[1]), None);
+        let english = language_index_of_coincidence(&Alphabet::english()).unwrap();
+        assert!(english > 0.06 && english < 0.07, "{}", english);
+        assert_eq!(language_index_of_coincidence(&Alphabet::new("AB").unwrap()), None);
+    }
+
+    #[test]
+    fn index_of_coincidence_scorer_prefers_monoalphabetic_text() {
+        let scorer = IndexOfCoincidenceScorer::english();
+        let message = "It was the best of times, it was the worst of times, it was the age of wisdom";
+        // The same text encrypted with Caesar and with Vigenère
+        assert_eq!(scorer.score(message), scorer.score("LWZDVWKHEHVWRIWLPHVLWZDVWKHZRUVWRIWLPHVLWZDVWKHDJHRIZLVGRP"));
+        assert!(scorer.score(message) > scorer.score("KKUPLHNVBTZRQWRXFSYZTLHQVYCLHFYKOUAGOVQXMKGJTWLYIVMUPWYUOB"));
+        assert!(IndexOfCoincidenceScorer::new(Alphabet::new("AB").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Fitness scores of decrypted messages, used to rank the candidates found by the hacks,
+//! so the right one doesn't have to be found by eye.
+//!
+//! The bundled English statistics (letter unigrams to quadgrams and common words) are in the `data`
+//! directory and embedded in the binary, `data/README.md` tells how they were counted and from which
+//! text. Tables for other languages or corpora can be loaded with `NgramScorer::from_file`.
+
+pub mod dictionary;
+pub mod frequencies;
+pub mod ngrams;
+
+use std::sync::OnceLock;
+
+use crate::alphabet::Alphabet;
+
+pub use dictionary::DictionaryScorer;
+pub use frequencies::{ChiSquaredScorer, IndexOfCoincidenceScorer};
+pub use ngrams::NgramScorer;
+
+/// Fitness of a message, the higher the more the message looks like a text of the language
+pub trait Scorer {
+    fn score(&self, message: &str) -> f64;
+}
+
+/// Any function can be used as a scorer
+impl<F: Fn(&str) -> f64> Scorer for F {
+    fn score(&self, message: &str) -> f64 {
+        self(message)
+    }
+}
+
+/// The candidates with their scores, the best one first
+pub fn rank<K>(candidates: Vec<(K, String)>, scorer: &dyn Scorer) -> Vec<(K, String, f64)> {
+    let mut ranked = candidates
+        .into_iter()
+        .map(|(key, message)| {
+            let score = scorer.score(&message);
+            (key, message, score)
+        })
+        .collect::<Vec<(K, String, f64)>>();
+    ranked.sort_by(|a, b| b.2.total_cmp(&a.2));
+    ranked
+}
+
+/// Ranks with the given scorer, or with the default scorer of the alphabet if there is none
+pub fn rank_or_default<K>(
+    candidates: Vec<(K, String)>,
+    scorer: Option<&dyn Scorer>,
+    alphabet: &Alphabet,
+) -> Vec<(K, String, f64)> {
+    match scorer {
+        Some(scorer) => rank(candidates, scorer),
+        None => rank(candidates, default_scorer(alphabet).as_ref()),
+    }
+}
+
+/// Quadgrams for the English alphabet, the chi-squared statistic for other alphabets with frequencies.
+/// Without frequencies all messages get the same score, so the order is kept.
+pub fn default_scorer(alphabet: &Alphabet) -> Box<dyn Scorer> {
+    if alphabet.symbols() == Alphabet::english().symbols() {
+        return Box::new(|message: &str| english_quadgrams().score(message));
+    }
+    match ChiSquaredScorer::new(alphabet.clone()) {
+        Ok(scorer) => Box::new(scorer),
+        Err(_) => Box::new(|_: &str| 0.0),
+    }
+}
+
+/// The English quadgrams are loaded once, as every hack without a scorer of its own needs them
+fn english_quadgrams() -> &'static NgramScorer {
+    static ENGLISH: OnceLock<NgramScorer> = OnceLock::new();
+    ENGLISH.get_or_init(NgramScorer::english_quadgrams)
+}
+
+#[cfg(test)]
+mod scoring_test {
+    use crate::alphabet::Alphabet;
+    use crate::scoring::{default_scorer, english_quadgrams, rank, NgramScorer, Scorer};
+
+    #[test]
+    fn rank_puts_best_first() {
+        let candidates = vec![(1, "XQZJK".to_owned()), (2, "HELLO".to_owned()), (3, "ZZZ".to_owned())];
+        let ranked = rank(candidates, &|message: &str| -(message.len() as f64));
+        assert_eq!(ranked.iter().map(|x| x.0).collect::<Vec<i32>>(), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn default_scorer_prefers_language() {
+        let scorer = default_scorer(&Alphabet::english());
+        assert!(scorer.score("MEETMEATTHEMALL") > scorer.score("LLAMEHTTAEMTEEM"));
+        assert_eq!(scorer.score("MEETMEATTHEMALL"), NgramScorer::english_quadgrams().score("MEETMEATTHEMALL"));
+        assert!(std::ptr::eq(english_quadgrams(), english_quadgrams()));
+        // The chi-squared statistic only sees the letter counts, so the other text is shifted
+        let scorer = default_scorer(&Alphabet::russian());
+        assert!(scorer.score("ВСТРЕТИМСЯВПАРКЕ") > scorer.score("ЕФХУИХЛПФВЕТГУНИ"));
+        assert_eq!(default_scorer(&Alphabet::new("ABC").unwrap()).score("ABC"), 0.0);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+use crate::scoring::Scorer;
+
+const ENGLISH_UNIGRAMS: &str = include_str!("../../data/english_unigrams.txt");
+const ENGLISH_BIGRAMS: &str = include_str!("../../data/english_bigrams.txt");
+const ENGLISH_TRIGRAMS: &str = include_str!("../../data/english_trigrams.txt");
+const ENGLISH_QUADGRAMS: &str = include_str!("../../data/english_quadgrams.txt");
+
+/// The biggest table that can be created, 26^5 n-grams
+const MAX_TABLE_SIZE: usize = 11_881_376;
+
+/// Scores with the log-likelihood of the n-grams (n consecutive letters) of the message,
+/// averaged by the count of the n-grams so messages of different lengths can be compared.
+/// Only the letters of the alphabet are used, n-grams missing from the table get a small floor probability.
+pub struct NgramScorer {
+    n: usize,
+    alphabet: Alphabet,
+    // The position of every symbol of the alphabet in both cases, the messages are scored many times by
+    // the hacks, so the ASCII symbols are looked up in an array
+    ascii: [Option<usize>; 128],
+    lookup: HashMap<char, usize>,
+    // By the positions of the letters in the alphabet, the first letter is the most significant
+    log_probabilities: Vec<f64>,
+    floor: f64,
+}
+
+impl NgramScorer {
+    pub fn english_unigrams() -> Self {
+        Self::from_counts(ENGLISH_UNIGRAMS, Alphabet::english()).unwrap()
+    }
+
+    pub fn english_bigrams() -> Self {
+        Self::from_counts(ENGLISH_BIGRAMS, Alphabet::english()).unwrap()
+    }
+
+    pub fn english_trigrams() -> Self {
+        Self::from_counts(ENGLISH_TRIGRAMS, Alphabet::english()).unwrap()
+    }
+
+    pub fn english_quadgrams() -> Self {
+        Self::from_counts(ENGLISH_QUADGRAMS, Alphabet::english()).unwrap()
+    }
+
+    /// The counts are given line by line as an n-gram and its count separated by whitespace, like "TION 364".
+    /// All n-grams should have the same length, empty lines are skipped
+    pub fn from_counts(counts: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let mut n = None;
+        let mut parsed = vec![];
+        for (number, line) in counts.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (ngram, count) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(ngram), Some(count), None) => (ngram, count),
+                _ => return Err(Self::invalid_line(number, "expected an n-gram and its count")),
+            };
+            let count = match count.parse::<f64>() {
+                Ok(count) if count.is_finite() && count > 0.0 => count,
+                _ => return Err(Self::invalid_line(number, "the count should be a positive number")),
+            };
+            let ngram = Self::positions(&alphabet, &alphabet.to_indices_strict(ngram)?);
+            if *n.get_or_insert(ngram.len()) != ngram.len() {
+                return Err(Self::invalid_line(number, "the n-grams have different lengths"));
+            }
+            parsed.push((ngram, count));
+        }
+        let n = n.ok_or(CipherError::EmptyInput)?;
+        let size = (alphabet.modulus() as usize)
+            .checked_pow(n as u32)
+            .filter(|size| *size <= MAX_TABLE_SIZE)
+            .ok_or_else(|| CipherError::InvalidCounts(format!("{}-grams are too long for the alphabet", n)))?;
+        let total = parsed.iter().map(|x| x.1).sum::<f64>();
+        let floor = (0.01 / total).log10();
+        let mut log_probabilities = vec![floor; size];
+        for (ngram, count) in parsed {
+            let index = Self::index(&ngram, alphabet.modulus() as usize);
+            log_probabilities[index] = (count / total).log10();
+        }
+        let mut lookup = HashMap::new();
+        for (position, symbol) in alphabet.symbols().iter().enumerate() {
+            lookup.extend(symbol.to_lowercase().chain(symbol.to_uppercase()).map(|s| (s, position)));
+            lookup.insert(*symbol, position);
+        }
+        let mut ascii = [None; 128];
+        for (symbol, position) in &lookup {
+            if symbol.is_ascii() {
+                ascii[*symbol as usize] = Some(*position);
+            }
+        }
+        Ok(NgramScorer {
+            n,
+            alphabet,
+            ascii,
+            lookup,
+            log_probabilities,
+            floor,
+        })
+    }
+
+    /// Reads the counts from a file in the format of `from_counts`
+    pub fn from_file(path: impl AsRef<Path>, alphabet: Alphabet) -> Result<Self, CipherError> {
+        let counts = fs::read_to_string(path.as_ref()).map_err(|error| {
+            CipherError::InvalidCounts(format!("can't read {}: {}", path.as_ref().display(), error))
+        })?;
+        Self::from_counts(&counts, alphabet)
+    }
+
+    /// The length of the n-grams
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    fn invalid_line(number: usize, reason: &str) -> CipherError {
+        CipherError::InvalidCounts(format!("line {}: {}", number + 1, reason))
+    }
+
+    /// Frequencies and tables are in the order of the symbols, which differs from the indices for one-based alphabets
+    fn positions(alphabet: &Alphabet, indices: &[u32]) -> Vec<usize> {
+        let modulus = alphabet.modulus();
+        let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+        indices.iter().map(|x| ((x + modulus - first) % modulus) as usize).collect()
+    }
+
+    fn letters(&self, message: &str) -> Vec<usize> {
+        let mut letters = Vec::with_capacity(message.len());
+        for c in message.chars() {
+            if c.is_ascii() {
+                letters.extend(self.ascii[c as usize]);
+                continue;
+            }
+            match self.lookup.get(&c) {
+                Some(position) => letters.push(*position),
+                None => letters.extend(Self::positions(&self.alphabet, &self.alphabet.to_indices(&c.to_string()))),
+            }
+        }
+        letters
+    }
+
+    fn index(ngram: &[usize], modulus: usize) -> usize {
+        ngram.iter().fold(0, |index, letter| index * modulus + letter)
+    }
+}
+
+impl Scorer for NgramScorer {
+    fn score(&self, message: &str) -> f64 {
+        let letters = self.letters(message);
+        if letters.len() < self.n {
+            return self.floor;
+        }
+        let modulus = self.alphabet.modulus() as usize;
+        let ngrams = letters
+            .windows(self.n)
+            .map(|ngram| self.log_probabilities[Self::index(ngram, modulus)]);
+        ngrams.sum::<f64>() / (letters.len() - self.n + 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod ngrams_test {
+    use crate::alphabet::Alphabet;
+    use crate::error::CipherError;
+    use crate::scoring::ngrams::NgramScorer;
+    use crate::scoring::Scorer;
+
+    #[test]
+    fn english_scores_higher_than_gibberish() {
+        let scorer = NgramScorer::english_quadgrams();
+        assert!(scorer.score("Meet me at the mall at nine") > scorer.score("QUPCVOZGTMBAOMBIXQHHI"));
+        assert!(scorer.score("the end of the school year") > scorer.score("eht dne fo eht loohcs raey"));
+        assert_eq!(scorer.score("abc"), scorer.score("xyz"));
+    }
+
+    #[test]
+    fn all_english_tables_work() {
+        let scorers = [
+            NgramScorer::english_unigrams(),
+            NgramScorer::english_bigrams(),
+            NgramScorer::english_trigrams(),
+            NgramScorer::english_quadgrams(),
+        ];
+        for (n, scorer) in scorers.iter().enumerate() {
+            assert_eq!(scorer.n(), n + 1);
+            assert!(scorer.score("Defend the east wall of the castle") > scorer.score("GHIHQGWKHHDVWZDOORIWKHFDVWOH"));
+        }
+    }
+
+    #[test]
+    fn custom_counts_work() {
+        let alphabet = Alphabet::new("AB").unwrap();
+        let scorer = NgramScorer::from_counts("AB 3\nBA 1\n\n", alphabet.clone()).unwrap();
+        assert_eq!(scorer.n(), 2);
+        // log10(3/4) per bigram
+        assert!((scorer.score("ABAB") - (0.75f64.log10() * 2.0 + 0.25f64.log10()) / 3.0).abs() < 1e-9);
+        assert!(scorer.score("ABAB") > scorer.score("AABB"));
+    }
+
+    #[test]
+    fn invalid_counts_are_rejected() {
+        let alphabet = Alphabet::new("AB").unwrap();
+        let invalid = |reason: &str| Err(CipherError::InvalidCounts(reason.to_owned()));
+        assert_eq!(
+            NgramScorer::from_counts("AB 3\nBAB 1", alphabet.clone()).map(|s| s.n()),
+            invalid("line 2: the n-grams have different lengths")
+        );
+        assert_eq!(
+            NgramScorer::from_counts("AB three", alphabet.clone()).map(|s| s.n()),
+            invalid("line 1: the count should be a positive number")
+        );
+        assert_eq!(
+            NgramScorer::from_counts("AB", alphabet.clone()).map(|s| s.n()),
+            invalid("line 1: expected an n-gram and its count")
+        );
+        assert_eq!(
+            NgramScorer::from_counts("AC 1", alphabet.clone()).map(|s| s.n()),
+            Err(CipherError::SymbolNotInAlphabet('C'))
+        );
+        assert_eq!(NgramScorer::from_counts("", alphabet).map(|s| s.n()), Err(CipherError::EmptyInput));
+        assert!(NgramScorer::from_file("missing.txt", Alphabet::english()).is_err());
+    }
+}
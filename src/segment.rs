@@ -0,0 +1,314 @@
+//! Splits run-together plaintext like "IMHUNGRYLETSGETAPIZZA" back into words.
+//!
+//! The most probable sequence of words is found with dynamic programming (the Viterbi algorithm)
+//! over the word and word pair probabilities counted from English text. Words missing from the
+//! dictionary get a probability that falls fast with their length, so most names stay in one piece.
+//! The ones that begin or end like a common short word can still be split though, like "FLORID A".
+//!
+//! ```
+//! use cryptology_for_beginners::attacks::CaesarHack;
+//! use cryptology_for_beginners::segment::{segment, Segment};
+//!
+//! assert_eq!(segment("ATTACKATDAWN"), "ATTACK AT DAWN");
+//! let (_, message, _) = CaesarHack::new("QUPCV OZGTM BAOMB IXQHH I".to_owned()).best().segmented().unwrap();
+//! assert_eq!(message, "IM HUNGRY LETS GET A PIZZA");
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::error::CipherError;
+
+const ENGLISH_WORD_COUNTS: &str = include_str!("../data/english_word_counts.txt");
+const ENGLISH_WORD_BIGRAMS: &str = include_str!("../data/english_word_bigrams.txt");
+/// Log10 penalty for each letter of a word missing from the dictionary. A smaller one lets known words
+/// run together with the names next to them ("IMETSTEVE"), a bigger one splits the names into short
+/// known words ("PORSC HE"). Around this one the most names stay whole in sentences like "I MET X YESTERDAY",
+/// though only about a third of them
+const UNKNOWN_LETTER_PENALTY: f64 = 1.4;
+
+/// Word segmentation with the word counts of a language. The bundled English counts are those
+/// of the corpus of `data`, so only its words are known
+pub struct Segmenter {
+    // Log10 probabilities of the words
+    words: HashMap<String, f64>,
+    // Log10 probability of the word after the previous word, by the previous word
+    bigrams: HashMap<String, HashMap<String, f64>>,
+    // Log10 of the count of all words, used for the words missing from the dictionary
+    log_total: f64,
+    longest_word: usize,
+}
+
+impl Segmenter {
+    pub fn english() -> Self {
+        Self::from_counts(ENGLISH_WORD_COUNTS, ENGLISH_WORD_BIGRAMS).unwrap()
+    }
+
+    /// The words are given line by line with their counts, like "HELLO 12", and the word pairs
+    /// the same way, like "HELLO WORLD 3". The pairs can be empty, then only the words are used
+    pub fn from_counts(words: &str, bigrams: &str) -> Result<Self, CipherError> {
+        let words = Self::parse(words, 1)?
+            .into_iter()
+            .map(|(mut word, count)| (word.remove(0), count))
+            .collect::<HashMap<String, f64>>();
+        if words.is_empty() {
+            return Err(CipherError::EmptyInput);
+        }
+        let total = words.values().sum::<f64>();
+        let mut pairs: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for (mut pair, count) in Self::parse(bigrams, 2)? {
+            let word = pair.pop().unwrap();
+            let previous = pair.pop().unwrap();
+            let previous_count = match words.get(&previous) {
+                Some(previous_count) if *previous_count >= count => *previous_count,
+                _ => {
+                    return Err(CipherError::InvalidCounts(format!(
+                        "the pair {} {} is more common than its first word",
+                        previous, word
+                    )))
+                }
+            };
+            pairs.entry(previous).or_default().insert(word, (count / previous_count).log10());
+        }
+        Ok(Segmenter {
+            longest_word: words.keys().map(|word| word.chars().count()).max().unwrap(),
+            words: words.into_iter().map(|(word, count)| (word, (count / total).log10())).collect(),
+            bigrams: pairs,
+            log_total: total.log10(),
+        })
+    }
+
+    /// Reads the counts from files in the format of `from_counts`
+    pub fn from_files(words: impl AsRef<Path>, bigrams: Option<&Path>) -> Result<Self, CipherError> {
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map_err(|error| CipherError::InvalidCounts(format!("can't read {}: {}", path.display(), error)))
+        };
+        let bigrams = match bigrams {
+            Some(path) => read(path)?,
+            None => String::new(),
+        };
+        Self::from_counts(&read(words.as_ref())?, &bigrams)
+    }
+
+    /// The words of the text, everything except the letters is skipped and the words are uppercase
+    pub fn segment(&self, text: &str) -> Vec<String> {
+        let letters = text
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(|c| c.to_uppercase())
+            .collect::<Vec<char>>();
+        let n = letters.len();
+        // best[end][length] is the best score of the text before `end` which ends with a word of
+        // the `length`, with the length of the word before it
+        let mut best: Vec<Vec<Option<(f64, usize)>>> = vec![vec![None; self.longest_word + 1]; n + 1];
+        best[0][0] = Some((0.0, 0));
+        for start in 0..n {
+            for length in 0..=self.longest_word.min(start) {
+                let score = match best[start][length] {
+                    Some((score, _)) => score,
+                    None => continue,
+                };
+                let previous = letters[start - length..start].iter().collect::<String>();
+                for next in 1..=self.longest_word.min(n - start) {
+                    let word = letters[start..start + next].iter().collect::<String>();
+                    let score = score + self.probability(&previous, &word);
+                    let cell = &mut best[start + next][next];
+                    if cell.is_none_or(|(best_score, _)| score > best_score) {
+                        *cell = Some((score, length));
+                    }
+                }
+            }
+        }
+        let mut length = match (0..=self.longest_word.min(n))
+            .filter_map(|length| best[n][length].map(|(score, _)| (length, score)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        {
+            Some((length, _)) => length,
+            None => return vec![],
+        };
+        let mut words = vec![];
+        let mut end = n;
+        while end > 0 {
+            words.push(letters[end - length..end].iter().collect());
+            let previous = best[end][length].unwrap().1;
+            end -= length;
+            length = previous;
+        }
+        words.reverse();
+        words
+    }
+
+    /// The words of the text joined with spaces
+    pub fn segment_text(&self, text: &str) -> String {
+        self.segment(text).join(" ")
+    }
+
+    /// Log10 probability of the word after the previous one (empty at the start of the text).
+    /// Unknown pairs fall back to the probability of the word alone
+    fn probability(&self, previous: &str, word: &str) -> f64 {
+        if let Some(probability) = self.bigrams.get(previous).and_then(|words| words.get(word)) {
+            return *probability;
+        }
+        match self.words.get(word) {
+            Some(probability) => *probability,
+            None => -self.log_total - UNKNOWN_LETTER_PENALTY * word.chars().count() as f64,
+        }
+    }
+
+    /// Lines of `fields` words followed by a count
+    fn parse(counts: &str, fields: usize) -> Result<Vec<(Vec<String>, f64)>, CipherError> {
+        let mut parsed = vec![];
+        for (number, line) in counts.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let invalid = |reason: &str| CipherError::InvalidCounts(format!("line {}: {}", number + 1, reason));
+            let parts = line.split_whitespace().collect::<Vec<&str>>();
+            if parts.len() != fields + 1 {
+                return Err(invalid("expected the words and their count"));
+            }
+            let count = match parts[fields].parse::<f64>() {
+                Ok(count) if count.is_finite() && count > 0.0 => count,
+                _ => return Err(invalid("the count should be a positive number")),
+            };
+            let words = parts[..fields].iter().map(|word| word.to_uppercase()).collect();
+            parsed.push((words, count));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Splits the text into English words with the bundled dictionary
+pub fn segment(text: &str) -> String {
+    english().segment_text(text)
+}
+
+/// The English segmenter is loaded once, as the dictionary is big
+fn english() -> &'static Segmenter {
+    static ENGLISH: OnceLock<Segmenter> = OnceLock::new();
+    ENGLISH.get_or_init(Segmenter::english)
+}
+
+/// Spaced output for the results of the hacks: the messages of the variants are split into words,
+/// the keys and the scores are kept
+pub trait Segment: Sized {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self;
+
+    /// Segmented with the bundled English dictionary
+    fn segmented(self) -> Self {
+        self.segmented_with(english())
+    }
+}
+
+impl Segment for String {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        segmenter.segment_text(&self)
+    }
+}
+
+impl<K> Segment for (K, String) {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        (self.0, self.1.segmented_with(segmenter))
+    }
+}
+
+impl<K> Segment for (K, String, f64) {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        (self.0, self.1.segmented_with(segmenter), self.2)
+    }
+}
+
+impl<A, B> Segment for (A, B, String) {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        (self.0, self.1, self.2.segmented_with(segmenter))
+    }
+}
+
+impl<A, B> Segment for (A, B, String, f64) {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        (self.0, self.1, self.2.segmented_with(segmenter), self.3)
+    }
+}
+
+impl<A, B, C> Segment for (A, B, C, String) {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        (self.0, self.1, self.2, self.3.segmented_with(segmenter))
+    }
+}
+
+impl<T: Segment> Segment for Vec<T> {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        self.into_iter().map(|x| x.segmented_with(segmenter)).collect()
+    }
+}
+
+impl<T: Segment> Segment for Option<T> {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        self.map(|x| x.segmented_with(segmenter))
+    }
+}
+
+impl<T: Segment, E> Segment for Result<T, E> {
+    fn segmented_with(self, segmenter: &Segmenter) -> Self {
+        self.map(|x| x.segmented_with(segmenter))
+    }
+}
+
+#[cfg(test)]
+mod segment_test {
+    use crate::attacks::{AffineCipherHack, HillDigraphCipherHack};
+    use crate::ciphers::{Affine, Cipher};
+    use crate::error::CipherError;
+    use crate::segment::{segment, Segment, Segmenter};
+
+    #[test]
+    fn segment_works() {
+        assert_eq!(segment("IMHUNGRYLETSGETAPIZZA"), "IM HUNGRY LETS GET A PIZZA");
+        assert_eq!(
+            segment("IFSTEVEWANTSTOKEEPTHEJOBHEMUSTWORKHARDER"),
+            "IF STEVE WANTS TO KEEP THE JOB HE MUST WORK HARDER"
+        );
+        assert_eq!(
+            segment("We had everything before us, we had nothing before us"),
+            "WE HAD EVERYTHING BEFORE US WE HAD NOTHING BEFORE US"
+        );
+        assert_eq!(segment(""), "");
+    }
+
+    #[test]
+    fn names_stay_whole() {
+        assert_eq!(segment("JENNIFERISHERE"), "JENNIFER IS HERE");
+        assert_eq!(segment("GIVEITTOKARENANDHISWIFE"), "GIVE IT TO KAREN AND HIS WIFE");
+        assert_eq!(segment("THELETTERFROMBRAZILWASLATE"), "THE LETTER FROM BRAZIL WAS LATE");
+        assert_eq!(segment("WEWENTTOOSLOLASTYEAR"), "WE WENT TO OSLO LAST YEAR");
+    }
+
+    #[test]
+    fn custom_counts_work() {
+        let segmenter = Segmenter::from_counts("ab 1\nc 1\nabc 6", "").unwrap();
+        assert_eq!(segmenter.segment("abc"), vec!["ABC".to_owned()]);
+        let segmenter = Segmenter::from_counts("ab 5\nc 5\nabc 1", "ab c 5").unwrap();
+        assert_eq!(segmenter.segment("abc"), vec!["AB".to_owned(), "C".to_owned()]);
+        assert_eq!(
+            Segmenter::from_counts("ab 1\nc 2", "ab c 2").err(),
+            Some(CipherError::InvalidCounts("the pair AB C is more common than its first word".to_owned()))
+        );
+        assert_eq!(
+            Segmenter::from_counts("ab", "").err(),
+            Some(CipherError::InvalidCounts("line 1: expected the words and their count".to_owned()))
+        );
+        assert_eq!(Segmenter::from_counts("", "").err(), Some(CipherError::EmptyInput));
+    }
+
+    #[test]
+    fn hack_results_can_be_segmented() {
+        let encrypted = Affine::new(7, 15).unwrap().encrypt("Don't forget to see the elephants".to_owned());
+        let (a, b, message, _) = AffineCipherHack::new(encrypted).best().segmented().unwrap();
+        assert_eq!((a, b, message.as_str()), (7, 15, "DONT FORGET TO SEE THE ELEPHANTS"));
+        let results = HillDigraphCipherHack::new("KMYEM UPAUO AHOJR YUKTT CACQC XXIYE DKSTQ ZXDAW".to_owned())
+            .check_with_crib("STEVE".to_owned())
+            .segmented()
+            .unwrap();
+        assert!(results.contains(&([5, 3, 9, 6], "IF STEVE WANTS TO KEEP THE JOB HE MUST WORK HARDER".to_owned())));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::alphabet::Alphabet;
+use crate::annealing::{annealing_builders, climb, Annealing, Cooling};
+use crate::random::Random;
+use crate::scoring::{default_scorer, Scorer};
+use crate::transposition::{to_order, untranspose};
+
+/// Keys up to this length are found by trying all the orders of the columns (7! = 5040 of them),
+/// longer ones by simulated annealing
+const EXHAUSTIVE_KEY_LENGTH: usize = 7;
+const COOLING: Cooling = Cooling::Linear(20.0);
+
+/// Breaks the columnar transposition (with a complete or an incomplete rectangle) by trying every key
+/// length: the short keys by decrypting with all the orders of the columns, the long ones with
+/// simulated annealing like `SubstitutionHack`, where two columns are swapped or a few columns are
+/// moved at every step, from a random order for every restart. The best order of every key length is kept.
+///
+/// Bigrams (`NgramScorer::english_bigrams()` as the scorer) are enough to put the columns next to each other.
+pub struct ColumnarHack {
+    encrypted_message: Vec<u32>,
+    alphabet: Alphabet,
+    min_key_length: usize,
+    max_key_length: usize,
+    scorer: Option<Box<dyn Scorer>>,
+    annealing: Annealing,
+}
+
+impl ColumnarHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        let encrypted_message = alphabet.to_indices(&encrypted_message);
+        ColumnarHack {
+            max_key_length: (encrypted_message.len() / 2).clamp(2, 12),
+            min_key_length: 2,
+            encrypted_message,
+            alphabet,
+            scorer: None,
+            annealing: Annealing::new(Self::RESTARTS, Self::ITERATIONS),
+        }
+    }
+
+    /// The longest key length that is tried, 12 by default (or half of the message if it's shorter)
+    pub fn with_max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = max_key_length.max(2);
+        self
+    }
+
+    /// Only the given key length is tried
+    pub fn with_key_length(mut self, key_length: usize) -> Self {
+        self.min_key_length = key_length.max(2);
+        self.max_key_length = key_length.max(2);
+        self
+    }
+
+    annealing_builders!(10, 5000);
+
+    /// The best order of the columns found for every key length (as the numbers of the columns, so it
+    /// can be given to `ColumnarCipher::from_order`) with the decrypted message and its score, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(Vec<usize>, String, f64)> {
+        if self.encrypted_message.is_empty() {
+            return vec![];
+        }
+        let default;
+        let scorer = match &self.scorer {
+            Some(scorer) => scorer.as_ref(),
+            None => {
+                default = default_scorer(&self.alphabet);
+                default.as_ref()
+            }
+        };
+        let mut random = Random::new(self.annealing.seed);
+        let max_key_length = self.max_key_length.min(self.encrypted_message.len());
+        let mut variants: Vec<(Vec<usize>, String, f64)> = vec![];
+        for length in self.min_key_length..=max_key_length {
+            let (ranks, score) = if length <= EXHAUSTIVE_KEY_LENGTH {
+                self.try_all(length, scorer)
+            } else {
+                self.search(length, scorer, &mut random)
+            };
+            let message = self.decrypt(&ranks);
+            if !variants.iter().any(|v| v.1 == message) {
+                variants.push((to_order(&ranks), message, score));
+            }
+        }
+        variants.sort_by(|a, b| b.2.total_cmp(&a.2));
+        variants
+    }
+
+    pub fn best(&self) -> Option<(Vec<usize>, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+
+    /// All the orders of the columns, generated by swaps (Heap's algorithm)
+    fn try_all(&self, length: usize, scorer: &dyn Scorer) -> (Vec<usize>, f64) {
+        let mut ranks = (0..length).collect::<Vec<usize>>();
+        let mut best = (ranks.clone(), scorer.score(&self.decrypt(&ranks)));
+        let mut counters = vec![0; length];
+        let mut i = 1;
+        while i < length {
+            if counters[i] < i {
+                let j = if i % 2 == 0 { 0 } else { counters[i] };
+                ranks.swap(j, i);
+                let score = scorer.score(&self.decrypt(&ranks));
+                if score > best.1 {
+                    best = (ranks.clone(), score);
+                }
+                counters[i] += 1;
+                i = 1;
+            } else {
+                counters[i] = 0;
+                i += 1;
+            }
+        }
+        best
+    }
+
+    fn search(&self, length: usize, scorer: &dyn Scorer, random: &mut Random) -> (Vec<usize>, f64) {
+        let mut best: Option<(Vec<usize>, f64)> = None;
+        let scale = self.encrypted_message.len() as f64;
+        let score_of = |ranks: &[usize]| scorer.score(&self.decrypt(ranks));
+        for _ in 0..self.annealing.restarts {
+            let mut ranks = (0..length).collect::<Vec<usize>>();
+            random.shuffle(&mut ranks);
+            let (ranks, _) = self.annealing.anneal(
+                ranks,
+                COOLING,
+                random,
+                |ranks| score_of(ranks) * scale,
+                |ranks, random| {
+                    Self::mutate(ranks, random);
+                    true
+                },
+            );
+            let score = score_of(&ranks);
+            let (ranks, score) = climb(ranks, score, score_of);
+            if best.as_ref().is_none_or(|b| score > b.1) {
+                best = Some((ranks, score));
+            }
+        }
+        best.unwrap()
+    }
+
+    /// Swaps the ranks of two columns, or moves a run of columns that are read one after another
+    /// (likely a part of the right order found already) to another place in the reading order
+    fn mutate(ranks: &mut [usize], random: &mut Random) {
+        let length = ranks.len();
+        if random.below(2) == 0 {
+            let (i, j) = (random.below(length), random.below(length));
+            ranks.swap(i, j);
+            return;
+        }
+        let mut columns = vec![0; length];
+        for (column, rank) in ranks.iter().enumerate() {
+            columns[*rank] = column;
+        }
+        let start = random.below(length);
+        let run = 1 + random.below(length - start);
+        let moved = columns.drain(start..start + run).collect::<Vec<usize>>();
+        let position = random.below(columns.len() + 1);
+        columns.splice(position..position, moved);
+        for (rank, column) in columns.iter().enumerate() {
+            ranks[*column] = rank;
+        }
+    }
+
+    fn decrypt(&self, ranks: &[usize]) -> String {
+        self.alphabet.from_indices(&untranspose(&self.encrypted_message, ranks))
+    }
+}
+
+#[cfg(test)]
+mod columnar_hack_test {
+    use crate::cipher::Cipher;
+    use crate::transposition::columnar::hacking::ColumnarHack;
+    use crate::transposition::columnar::manipulations::ColumnarCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us";
+
+    #[test]
+    fn short_keys_are_found() {
+        let cipher = ColumnarCipher::new("dickens").unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        let (key, message, _) = ColumnarHack::new(encrypted.clone()).with_max_key_length(7).best().unwrap();
+        assert_eq!(key, cipher.key());
+        assert_eq!(message, cipher.decrypt(encrypted).unwrap());
+        let cipher = ColumnarCipher::new("zebra").unwrap().with_padding(true);
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        let variants = ColumnarHack::new(encrypted).with_max_key_length(6).get_ranked_variants();
+        assert_eq!(variants[0].0, cipher.key());
+        assert_eq!(variants.len(), 5);
+    }
+
+    #[test]
+    fn long_keys_are_found() {
+        let cipher = ColumnarCipher::new("cryptologist").unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        for seed in 0..5 {
+            let hack = ColumnarHack::new(encrypted.clone()).with_key_length(12).with_seed(seed);
+            let (key, message, _) = hack.best().unwrap();
+            assert_eq!(key, cipher.key(), "seed {}", seed);
+            assert_eq!(message, cipher.decrypt(encrypted.clone()).unwrap());
+        }
+    }
+
+    #[test]
+    fn empty_message_has_no_variants() {
+        assert!(ColumnarHack::new("".to_owned()).best().is_none());
+        assert_eq!(ColumnarHack::new("ab".to_owned()).get_ranked_variants().len(), 1);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::transposition::{keyword_ranks, order_ranks, pad, to_order, transpose, untranspose};
+
+/// The message is written into the rows of a table with a column for every letter of the key and
+/// read out column by column, in the alphabetical order of the letters of the key (repeated letters
+/// from left to right). The last row is incomplete unless the padding is on.
+///
+/// ```
+/// use cryptology_for_beginners::cipher::Cipher;
+/// use cryptology_for_beginners::transposition::columnar::manipulations::ColumnarCipher;
+///
+/// let cipher = ColumnarCipher::new("zebras").unwrap();
+/// assert_eq!(cipher.key(), vec![6, 3, 2, 4, 1, 5]);
+/// assert_eq!(cipher.encrypt("We are discovered, flee at once".to_owned()), "EVLNA CDTES EAROF ODEEC WIREE");
+/// ```
+pub struct ColumnarCipher {
+    // The rank of every column from 0
+    ranks: Vec<usize>,
+    padding: bool,
+    alphabet: Alphabet,
+}
+
+impl ColumnarCipher {
+    pub fn new(keyword: &str) -> Result<Self, CipherError> {
+        Self::with_alphabet(keyword, Alphabet::english())
+    }
+
+    pub fn with_alphabet(keyword: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        Ok(ColumnarCipher {
+            ranks: keyword_ranks(keyword, &alphabet, false)?,
+            padding: false,
+            alphabet,
+        })
+    }
+
+    /// The key as the numbers of the columns in the reading order, like [6, 3, 2, 4, 1, 5] for "zebras"
+    pub fn from_order(order: &[usize]) -> Result<Self, CipherError> {
+        Self::from_order_with_alphabet(order, Alphabet::english())
+    }
+
+    pub fn from_order_with_alphabet(order: &[usize], alphabet: Alphabet) -> Result<Self, CipherError> {
+        Ok(ColumnarCipher {
+            ranks: order_ranks(order, false)?,
+            padding: false,
+            alphabet,
+        })
+    }
+
+    /// Fills the last row with X (the complete rectangle), so all the columns have the same length.
+    /// Off by default
+    pub fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The length of the padded messages should be a multiple of it
+    pub(crate) fn block_size(&self) -> usize {
+        if self.padding {
+            self.ranks.len()
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn encrypt_indices(&self, message: &[u32]) -> Vec<u32> {
+        let mut message = message.to_vec();
+        pad(&mut message, self.block_size(), &self.alphabet);
+        transpose(&message, &self.ranks)
+    }
+
+    pub(crate) fn decrypt_indices(&self, encrypted_message: &[u32]) -> Result<Vec<u32>, CipherError> {
+        if self.padding && !encrypted_message.len().is_multiple_of(self.ranks.len()) {
+            return Err(CipherError::InvalidLength {
+                length: encrypted_message.len(),
+                block_size: self.ranks.len(),
+            });
+        }
+        Ok(untranspose(encrypted_message, &self.ranks))
+    }
+}
+
+impl Cipher for ColumnarCipher {
+    /// The numbers of the columns in the reading order
+    type Key = Vec<usize>;
+
+    fn name(&self) -> &'static str {
+        "Columnar transposition"
+    }
+
+    fn key(&self) -> Vec<usize> {
+        to_order(&self.ranks)
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let encrypted = self.encrypt_indices(&self.alphabet.to_indices(&message));
+        Grouping::default().format(&self.alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let decrypted = self.decrypt_indices(&self.alphabet.to_indices(&encrypted_message))?;
+        Ok(self.alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod columnar_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::columnar::manipulations::ColumnarCipher;
+
+    #[test]
+    fn incomplete_rectangle_works() {
+        let cipher = ColumnarCipher::new("zebras").unwrap();
+        let encrypted = cipher.encrypt("We are discovered, flee at once".to_owned());
+        assert_eq!(encrypted, "EVLNA CDTES EAROF ODEEC WIREE");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+        assert_eq!(
+            ColumnarCipher::from_order(&[6, 3, 2, 4, 1, 5]).unwrap().encrypt("We are discovered, flee at once".to_owned()),
+            "EVLNA CDTES EAROF ODEEC WIREE"
+        );
+    }
+
+    #[test]
+    fn complete_rectangle_works() {
+        let cipher = ColumnarCipher::new("zebras").unwrap().with_padding(true);
+        let encrypted = cipher.encrypt("We are discovered, flee at once".to_owned());
+        assert_eq!(encrypted, "EVLNX ACDTX ESEAX ROFOX DEECX WIREE");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVEREDFLEEATONCEXXXXX".to_owned()));
+        assert_eq!(
+            cipher.decrypt("EVLNA".to_owned()),
+            Err(CipherError::InvalidLength { length: 5, block_size: 6 })
+        );
+    }
+
+    #[test]
+    fn works_with_other_alphabets() {
+        let cipher = ColumnarCipher::with_alphabet("ключ", Alphabet::russian()).unwrap();
+        assert_eq!(cipher.key(), vec![1, 2, 4, 3]);
+        let encrypted = cipher.encrypt("Съешь же ещё этих мягких французских булок".to_owned());
+        assert_eq!(
+            cipher.decrypt(encrypted),
+            Ok("СЪЕШЬЖЕЕЩЁЭТИХМЯГКИХФРАНЦУЗСКИХБУЛОК".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        assert_eq!(ColumnarCipher::new("a1").err(), Some(CipherError::SymbolNotInAlphabet('1')));
+        assert_eq!(
+            ColumnarCipher::from_order(&[1, 3]).err(),
+            Some(CipherError::InvalidKey(
+                "the numbers of the columns should be the numbers from 1 to 2".to_owned()
+            ))
+        );
+    }
+}
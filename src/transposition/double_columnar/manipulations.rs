@@ -0,0 +1,100 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::modular::gcd;
+use crate::transposition::columnar::manipulations::ColumnarCipher;
+use crate::transposition::pad;
+
+/// Columnar transposition applied twice, usually with two different keys. The letters that were
+/// neighbours in the message end up far from each other, so it is much harder to break than
+/// a single transposition. With the padding the message is filled with X until both tables are complete.
+pub struct DoubleColumnarCipher {
+    first: ColumnarCipher,
+    second: ColumnarCipher,
+}
+
+impl DoubleColumnarCipher {
+    pub fn new(first_keyword: &str, second_keyword: &str) -> Result<Self, CipherError> {
+        Self::with_ciphers(ColumnarCipher::new(first_keyword)?, ColumnarCipher::new(second_keyword)?)
+    }
+
+    /// The transpositions in the order they are applied, they should have the same alphabet
+    pub fn with_ciphers(first: ColumnarCipher, second: ColumnarCipher) -> Result<Self, CipherError> {
+        if first.alphabet() != second.alphabet() {
+            return Err(CipherError::InvalidKey(
+                "the transpositions have different alphabets".to_owned(),
+            ));
+        }
+        Ok(DoubleColumnarCipher { first, second })
+    }
+}
+
+impl Cipher for DoubleColumnarCipher {
+    /// The numbers of the columns of both transpositions in the reading order
+    type Key = (Vec<usize>, Vec<usize>);
+
+    fn name(&self) -> &'static str {
+        "Double columnar transposition"
+    }
+
+    fn key(&self) -> (Vec<usize>, Vec<usize>) {
+        (self.first.key(), self.second.key())
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        self.first.alphabet()
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let alphabet = self.alphabet();
+        let mut message = alphabet.to_indices(&message);
+        let (a, b) = (self.first.block_size(), self.second.block_size());
+        pad(&mut message, a * b / gcd(a as i64, b as i64) as usize, alphabet);
+        let encrypted = self.second.encrypt_indices(&self.first.encrypt_indices(&message));
+        Grouping::default().format(&alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let alphabet = self.alphabet();
+        let decrypted = self
+            .first
+            .decrypt_indices(&self.second.decrypt_indices(&alphabet.to_indices(&encrypted_message))?)?;
+        Ok(alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod double_columnar_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::columnar::manipulations::ColumnarCipher;
+    use crate::transposition::double_columnar::manipulations::DoubleColumnarCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = DoubleColumnarCipher::new("zebras", "striped").unwrap();
+        assert_eq!(cipher.key(), (vec![6, 3, 2, 4, 1, 5], vec![6, 7, 5, 3, 4, 2, 1]));
+        let encrypted = cipher.encrypt("We are discovered, flee at once".to_owned());
+        assert_eq!(encrypted, ColumnarCipher::new("striped").unwrap().encrypt("EVLNACDTESEAROFODEECWIREE".to_owned()));
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+    }
+
+    #[test]
+    fn padding_works() {
+        let cipher = DoubleColumnarCipher::with_ciphers(
+            ColumnarCipher::from_order(&[3, 1, 2, 5, 4]).unwrap().with_padding(true),
+            ColumnarCipher::from_order(&[2, 4, 1, 3]).unwrap().with_padding(true),
+        )
+        .unwrap();
+        let encrypted = cipher.encrypt("attack at dawn".to_owned());
+        assert_eq!(cipher.decrypt(encrypted), Ok("ATTACKATDAWNXXXXXXXX".to_owned()));
+        assert_eq!(
+            cipher.decrypt("ATTACK".to_owned()),
+            Err(CipherError::InvalidLength { length: 6, block_size: 4 })
+        );
+        let russian = ColumnarCipher::with_alphabet("ключ", Alphabet::russian()).unwrap();
+        assert!(DoubleColumnarCipher::with_ciphers(ColumnarCipher::new("key").unwrap(), russian).is_err());
+    }
+}
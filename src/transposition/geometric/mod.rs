@@ -0,0 +1,6 @@
+//! Transpositions by the shape of a figure: the zigzag of the rail fence, the rod of the scytale and
+//! the routes through a grid. Their keys are a few small numbers, so the hacks simply try all of them.
+
+pub mod rail_fence;
+pub mod route;
+pub mod scytale;
@@ -0,0 +1,88 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::scoring::{rank_or_default, Scorer};
+use crate::transposition::geometric::rail_fence::manipulations::RailFenceCipher;
+
+/// Tries every number of rails up to the maximum with every offset
+pub struct RailFenceHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    max_rails: usize,
+    scorer: Option<Box<dyn Scorer>>,
+}
+
+impl RailFenceHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        let length = alphabet.to_indices(&encrypted_message).len();
+        RailFenceHack {
+            encrypted_message,
+            alphabet,
+            max_rails: length.clamp(2, 20),
+            scorer: None,
+        }
+    }
+
+    /// The most rails that are tried, 20 by default (or the length of the message if it's shorter)
+    pub fn with_max_rails(mut self, max_rails: usize) -> Self {
+        self.max_rails = max_rails.max(2);
+        self
+    }
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    /// The decrypted message for every number of the rails and offset
+    pub fn get_all_variants(&self) -> Vec<((usize, usize), String)> {
+        (2..=self.max_rails)
+            .flat_map(|rails| (0..2 * (rails - 1)).map(move |offset| (rails, offset)))
+            .map(|(rails, offset)| {
+                let cipher = RailFenceCipher::with_alphabet(rails, self.alphabet.clone())
+                    .unwrap()
+                    .with_offset(offset);
+                ((rails, offset), cipher.decrypt(self.encrypted_message.clone()).unwrap())
+            })
+            .collect()
+    }
+
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<((usize, usize), String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<((usize, usize), String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod rail_fence_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::transposition::geometric::rail_fence::hacking::RailFenceHack;
+    use crate::transposition::geometric::rail_fence::manipulations::RailFenceCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity";
+
+    #[test]
+    fn best_finds_the_key() {
+        let encrypted = RailFenceCipher::new(7).unwrap().with_offset(3).encrypt(MESSAGE.to_owned());
+        let (key, message, _) = RailFenceHack::new(encrypted).best().unwrap();
+        assert_eq!(key, (7, 3));
+        assert_eq!(message, Alphabet::english().normalize(MESSAGE));
+    }
+
+    #[test]
+    fn all_keys_are_tried() {
+        let hack = RailFenceHack::new("WECRUOERDSOEERNTNEAIVDAC".to_owned()).with_max_rails(4);
+        assert_eq!(hack.get_all_variants().len(), 2 + 4 + 6);
+        assert_eq!(hack.best().unwrap().1, "WEAREDISCOVEREDRUNATONCE");
+    }
+}
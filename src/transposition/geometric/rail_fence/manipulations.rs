@@ -0,0 +1,105 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::transposition::{permute, unpermute};
+
+/// The message is written in a zigzag down and up the rails and read out rail by rail.
+/// The offset starts the zigzag further along its cycle (of 2 × (rails - 1) letters),
+/// so the first letter isn't on the top rail.
+pub struct RailFenceCipher {
+    rails: usize,
+    offset: usize,
+    alphabet: Alphabet,
+}
+
+impl RailFenceCipher {
+    pub fn new(rails: usize) -> Result<Self, CipherError> {
+        Self::with_alphabet(rails, Alphabet::english())
+    }
+
+    pub fn with_alphabet(rails: usize, alphabet: Alphabet) -> Result<Self, CipherError> {
+        if rails < 2 {
+            return Err(CipherError::InvalidKey("the rail fence needs at least 2 rails".to_owned()));
+        }
+        Ok(RailFenceCipher {
+            rails,
+            offset: 0,
+            alphabet,
+        })
+    }
+
+    /// The offset is taken by the length of the cycle, 0 by default
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset % (2 * (self.rails - 1));
+        self
+    }
+
+    fn order(&self, length: usize) -> Vec<usize> {
+        let cycle = 2 * (self.rails - 1);
+        let rail = |i: usize| {
+            let step = (i + self.offset) % cycle;
+            step.min(cycle - step)
+        };
+        let mut order = (0..length).collect::<Vec<usize>>();
+        order.sort_by_key(|i| rail(*i));
+        order
+    }
+}
+
+impl Cipher for RailFenceCipher {
+    /// The number of the rails and the offset
+    type Key = (usize, usize);
+
+    fn name(&self) -> &'static str {
+        "Rail fence"
+    }
+
+    fn key(&self) -> (usize, usize) {
+        (self.rails, self.offset)
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let message = self.alphabet.to_indices(&message);
+        let encrypted = permute(&message, &self.order(message.len()));
+        Grouping::default().format(&self.alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.alphabet.to_indices(&encrypted_message);
+        let decrypted = unpermute(&encrypted_message, &self.order(encrypted_message.len()));
+        Ok(self.alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod rail_fence_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::geometric::rail_fence::manipulations::RailFenceCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = RailFenceCipher::new(3).unwrap();
+        let encrypted = cipher.encrypt("We are discovered, run at once".to_owned());
+        assert_eq!(encrypted, "WECRU OERDS OEERN TNEAI VDAC");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVEREDRUNATONCE".to_owned()));
+    }
+
+    #[test]
+    fn offset_works() {
+        let cipher = RailFenceCipher::new(3).unwrap().with_offset(5);
+        assert_eq!(cipher.key(), (3, 1));
+        let encrypted = cipher.encrypt("We are discovered".to_owned());
+        assert_eq!(encrypted, "RSEWA EICVR DEDOE");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVERED".to_owned()));
+        assert_eq!(
+            RailFenceCipher::new(1).err(),
+            Some(CipherError::InvalidKey("the rail fence needs at least 2 rails".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::scoring::{rank_or_default, Scorer};
+use crate::transposition::geometric::route::manipulations::{Route, RouteCipher};
+
+/// Tries every route with every number of the columns of the grid, from 2 to the length of the message
+pub struct RouteHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
+}
+
+impl RouteHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        RouteHack {
+            encrypted_message,
+            alphabet,
+            scorer: None,
+        }
+    }
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    /// The decrypted message for every route and number of the columns
+    pub fn get_all_variants(&self) -> Vec<((Route, usize), String)> {
+        let length = self.alphabet.to_indices(&self.encrypted_message).len();
+        Route::all()
+            .iter()
+            .flat_map(|route| (2..length).map(move |columns| (*route, columns)))
+            .map(|(route, columns)| {
+                let cipher = RouteCipher::with_alphabet(route, columns, self.alphabet.clone()).unwrap();
+                ((route, columns), cipher.decrypt(self.encrypted_message.clone()).unwrap())
+            })
+            .collect()
+    }
+
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<((Route, usize), String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<((Route, usize), String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod route_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::transposition::geometric::route::hacking::RouteHack;
+    use crate::transposition::geometric::route::manipulations::{Route, RouteCipher};
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity";
+
+    #[test]
+    fn best_finds_the_key() {
+        for route in Route::all() {
+            let encrypted = RouteCipher::new(route, 8).unwrap().encrypt(MESSAGE.to_owned());
+            let (key, message, _) = RouteHack::new(encrypted).best().unwrap();
+            assert_eq!(key, (route, 8));
+            assert_eq!(message, Alphabet::english().normalize(MESSAGE));
+        }
+    }
+
+    #[test]
+    fn all_keys_are_tried() {
+        assert_eq!(RouteHack::new("ABCDEF".to_owned()).get_all_variants().len(), 3 * 4);
+        assert!(RouteHack::new("".to_owned()).best().is_none());
+    }
+}
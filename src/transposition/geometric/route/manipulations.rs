@@ -0,0 +1,169 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::transposition::{permute, unpermute};
+
+/// The way the grid is read out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Clockwise around the grid from the top left corner, then around the inner part and so on
+    Spiral,
+    /// The columns from the left, down the first one, up the second one and so on
+    Snake,
+    /// The diagonals from the top left corner, every one from its top right end down to the left
+    Diagonal,
+}
+
+impl Route {
+    pub fn all() -> [Route; 3] {
+        [Route::Spiral, Route::Snake, Route::Diagonal]
+    }
+}
+
+/// The message is written into the rows of a grid with the given number of columns and read out
+/// along the route. The cells of the incomplete last row are skipped.
+pub struct RouteCipher {
+    route: Route,
+    columns: usize,
+    alphabet: Alphabet,
+}
+
+impl RouteCipher {
+    pub fn new(route: Route, columns: usize) -> Result<Self, CipherError> {
+        Self::with_alphabet(route, columns, Alphabet::english())
+    }
+
+    pub fn with_alphabet(route: Route, columns: usize, alphabet: Alphabet) -> Result<Self, CipherError> {
+        if columns == 0 {
+            return Err(CipherError::InvalidKey("the grid should have at least 1 column".to_owned()));
+        }
+        Ok(RouteCipher {
+            route,
+            columns,
+            alphabet,
+        })
+    }
+
+    /// The positions of the letters of the message along the route
+    fn order(&self, length: usize) -> Vec<usize> {
+        let (rows, columns) = (length.div_ceil(self.columns), self.columns);
+        let cells = match self.route {
+            Route::Spiral => spiral(rows, columns),
+            Route::Snake => (0..columns)
+                .flat_map(|column| {
+                    let cells = (0..rows).map(move |row| (row, column));
+                    if column % 2 == 0 {
+                        cells.collect::<Vec<(usize, usize)>>()
+                    } else {
+                        cells.rev().collect()
+                    }
+                })
+                .collect(),
+            Route::Diagonal => (0..rows + columns)
+                .flat_map(|sum| (0..=sum).map(move |row| (row, sum - row)))
+                .filter(|(row, column)| *row < rows && *column < columns)
+                .collect(),
+        };
+        cells
+            .into_iter()
+            .map(|(row, column)| row * columns + column)
+            .filter(|x| *x < length)
+            .collect()
+    }
+}
+
+/// The cells of the grid clockwise from the outside in
+fn spiral(rows: usize, columns: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::with_capacity(rows * columns);
+    let (mut top, mut left) = (0, 0);
+    let (mut bottom, mut right) = (rows, columns);
+    while top < bottom && left < right {
+        cells.extend((left..right).map(|column| (top, column)));
+        cells.extend((top + 1..bottom).map(|row| (row, right - 1)));
+        if bottom - top > 1 {
+            cells.extend((left..right - 1).rev().map(|column| (bottom - 1, column)));
+        }
+        if right - left > 1 {
+            cells.extend((top + 1..bottom - 1).rev().map(|row| (row, left)));
+        }
+        top += 1;
+        left += 1;
+        bottom -= 1;
+        right -= 1;
+    }
+    cells
+}
+
+impl Cipher for RouteCipher {
+    /// The route and the number of the columns
+    type Key = (Route, usize);
+
+    fn name(&self) -> &'static str {
+        "Route"
+    }
+
+    fn key(&self) -> (Route, usize) {
+        (self.route, self.columns)
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let message = self.alphabet.to_indices(&message);
+        let encrypted = permute(&message, &self.order(message.len()));
+        Grouping::default().format(&self.alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.alphabet.to_indices(&encrypted_message);
+        let decrypted = unpermute(&encrypted_message, &self.order(encrypted_message.len()));
+        Ok(self.alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod route_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::geometric::route::manipulations::{Route, RouteCipher};
+
+    // W E A R
+    // E D I S
+    // C O V E
+    // R E D
+    const MESSAGE: &str = "We are discovered";
+
+    #[test]
+    fn spiral_works() {
+        let cipher = RouteCipher::new(Route::Spiral, 4).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        assert_eq!(encrypted, "WEARS EDERC EDIVO");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVERED".to_owned()));
+        let cipher = RouteCipher::new(Route::Spiral, 5).unwrap();
+        // W E A R E / D I S C O / V E R E D
+        assert_eq!(cipher.encrypt(MESSAGE.to_owned()), "WEARE ODERE VDISC");
+    }
+
+    #[test]
+    fn snake_works() {
+        let cipher = RouteCipher::new(Route::Snake, 4).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        assert_eq!(encrypted, "WECRE ODEAI VDESR");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVERED".to_owned()));
+    }
+
+    #[test]
+    fn diagonal_works() {
+        let cipher = RouteCipher::new(Route::Diagonal, 4).unwrap();
+        let encrypted = cipher.encrypt(MESSAGE.to_owned());
+        assert_eq!(encrypted, "WEEAD CRIOR SVEED");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVERED".to_owned()));
+        assert_eq!(
+            RouteCipher::new(Route::Diagonal, 0).err(),
+            Some(CipherError::InvalidKey("the grid should have at least 1 column".to_owned()))
+        );
+    }
+}
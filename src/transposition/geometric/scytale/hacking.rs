@@ -0,0 +1,80 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::scoring::{rank_or_default, Scorer};
+use crate::transposition::geometric::scytale::manipulations::ScytaleCipher;
+
+/// Tries every diameter of the rod, from 2 letters to the length of the message. The diameters
+/// that give the same number of the letters along the rod give the same table, so only the first
+/// one of them is tried
+pub struct ScytaleHack {
+    encrypted_message: String,
+    alphabet: Alphabet,
+    scorer: Option<Box<dyn Scorer>>,
+}
+
+impl ScytaleHack {
+    pub fn new(encrypted_message: String) -> Self {
+        Self::with_alphabet(encrypted_message, Alphabet::english())
+    }
+
+    pub fn with_alphabet(encrypted_message: String, alphabet: Alphabet) -> Self {
+        ScytaleHack {
+            encrypted_message,
+            alphabet,
+            scorer: None,
+        }
+    }
+
+    /// The scorer used to rank the variants, quadgrams by default for English
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    /// The decrypted message for every diameter
+    pub fn get_all_variants(&self) -> Vec<(usize, String)> {
+        let length = self.alphabet.to_indices(&self.encrypted_message).len();
+        (2..length)
+            .filter(|diameter| *diameter == 2 || length.div_ceil(*diameter) != length.div_ceil(diameter - 1))
+            .map(|diameter| {
+                let cipher = ScytaleCipher::with_alphabet(diameter, self.alphabet.clone()).unwrap();
+                (diameter, cipher.decrypt(self.encrypted_message.clone()).unwrap())
+            })
+            .collect()
+    }
+
+    /// All variants with their scores, the best one first
+    pub fn get_ranked_variants(&self) -> Vec<(usize, String, f64)> {
+        rank_or_default(self.get_all_variants(), self.scorer.as_deref(), &self.alphabet)
+    }
+
+    pub fn best(&self) -> Option<(usize, String, f64)> {
+        self.get_ranked_variants().into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod scytale_hack_test {
+    use crate::alphabet::Alphabet;
+    use crate::cipher::Cipher;
+    use crate::transposition::geometric::scytale::hacking::ScytaleHack;
+    use crate::transposition::geometric::scytale::manipulations::ScytaleCipher;
+
+    const MESSAGE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity";
+
+    #[test]
+    fn best_finds_the_key() {
+        let encrypted = ScytaleCipher::new(9).unwrap().encrypt(MESSAGE.to_owned());
+        let (diameter, message, _) = ScytaleHack::new(encrypted).best().unwrap();
+        assert_eq!(diameter, 9);
+        assert_eq!(message, Alphabet::english().normalize(MESSAGE));
+    }
+
+    #[test]
+    fn same_tables_are_tried_once() {
+        // 5 to 9 letters around the rod all give tables with 2 letters along it
+        let variants = ScytaleHack::new("ABCDEFGHIJ".to_owned()).get_all_variants();
+        assert_eq!(variants.iter().map(|v| v.0).collect::<Vec<usize>>(), vec![2, 3, 4, 5]);
+    }
+}
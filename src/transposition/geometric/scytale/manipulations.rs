@@ -0,0 +1,88 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::transposition::{transpose, untranspose};
+
+/// A strip wound around a rod: the message is written along the rod in as many rows as the letters
+/// that fit around it (the diameter), and the unwound strip has the letters column by column.
+/// The last row is shorter if the message doesn't fill the rod.
+pub struct ScytaleCipher {
+    diameter: usize,
+    alphabet: Alphabet,
+}
+
+impl ScytaleCipher {
+    pub fn new(diameter: usize) -> Result<Self, CipherError> {
+        Self::with_alphabet(diameter, Alphabet::english())
+    }
+
+    pub fn with_alphabet(diameter: usize, alphabet: Alphabet) -> Result<Self, CipherError> {
+        if diameter == 0 {
+            return Err(CipherError::InvalidKey("the diameter should be at least 1".to_owned()));
+        }
+        Ok(ScytaleCipher { diameter, alphabet })
+    }
+
+    /// The columns of the table in order, as many as the letters along the rod
+    fn ranks(&self, length: usize) -> Vec<usize> {
+        (0..length.div_ceil(self.diameter).max(1)).collect()
+    }
+}
+
+impl Cipher for ScytaleCipher {
+    /// The number of the letters around the rod
+    type Key = usize;
+
+    fn name(&self) -> &'static str {
+        "Scytale"
+    }
+
+    fn key(&self) -> usize {
+        self.diameter
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let message = self.alphabet.to_indices(&message);
+        let encrypted = transpose(&message, &self.ranks(message.len()));
+        Grouping::default().format(&self.alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let encrypted_message = self.alphabet.to_indices(&encrypted_message);
+        let decrypted = untranspose(&encrypted_message, &self.ranks(encrypted_message.len()));
+        Ok(self.alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod scytale_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::geometric::scytale::manipulations::ScytaleCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = ScytaleCipher::new(4).unwrap();
+        let encrypted = cipher.encrypt("I am hurt very badly help".to_owned());
+        assert_eq!(encrypted, "IRYYA TBHMV AEHED LURLP");
+        assert_eq!(cipher.decrypt(encrypted), Ok("IAMHURTVERYBADLYHELP".to_owned()));
+    }
+
+    #[test]
+    fn incomplete_rod_works() {
+        let cipher = ScytaleCipher::new(3).unwrap();
+        // IAMH / URTV / ERY
+        assert_eq!(cipher.encrypt("I am hurt very".to_owned()), "IUEAR RMTYH V");
+        assert_eq!(cipher.decrypt("IUEARRMTYHV".to_owned()), Ok("IAMHURTVERY".to_owned()));
+        assert_eq!(cipher.decrypt("".to_owned()), Ok("".to_owned()));
+        assert_eq!(
+            ScytaleCipher::new(0).err(),
+            Some(CipherError::InvalidKey("the diameter should be at least 1".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Transposition ciphers, which keep the letters of the message and only change their order.
+//!
+//! The columnar ciphers write the message into the rows of a table and read it out column by column
+//! in the order of the key, given as a keyword (its letters in alphabetical order) or as the numbers
+//! of the columns. The simpler transpositions by the shape of a figure are in [`geometric`].
+
+pub mod columnar;
+pub mod double_columnar;
+pub mod geometric;
+pub mod myszkowski;
+
+use crate::alphabet::Alphabet;
+use crate::error::CipherError;
+use crate::polybius::filler;
+
+/// The rank of every column from 0, by the letters of the keyword in the alphabet. Repeated letters
+/// get the same rank if `ties` is true, otherwise they are ranked from left to right
+pub(crate) fn keyword_ranks(keyword: &str, alphabet: &Alphabet, ties: bool) -> Result<Vec<usize>, CipherError> {
+    let first = alphabet.index_of(alphabet.symbols()[0]).unwrap();
+    let modulus = alphabet.modulus();
+    let letters = alphabet
+        .to_indices_strict(keyword)?
+        .into_iter()
+        .map(|x| ((x + modulus - first) % modulus) as usize)
+        .collect::<Vec<usize>>();
+    if letters.is_empty() {
+        return Err(CipherError::InvalidKey("the key is empty".to_owned()));
+    }
+    Ok(ranks(&letters, ties))
+}
+
+/// The rank of every column from 0 for the numbers of the columns, which should be the numbers from 1
+/// to the number of the columns, or any numbers from 1 if `ties` is true (equal numbers get the same rank)
+pub(crate) fn order_ranks(order: &[usize], ties: bool) -> Result<Vec<usize>, CipherError> {
+    if order.is_empty() {
+        return Err(CipherError::InvalidKey("the key is empty".to_owned()));
+    }
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    let valid = if ties {
+        sorted[0] >= 1
+    } else {
+        sorted.iter().enumerate().all(|(i, x)| *x == i + 1)
+    };
+    if !valid {
+        return Err(CipherError::InvalidKey(if ties {
+            "the numbers of the columns should start from 1".to_owned()
+        } else {
+            format!("the numbers of the columns should be the numbers from 1 to {}", order.len())
+        }));
+    }
+    Ok(ranks(order, ties))
+}
+
+/// Adds X to the message until its length is a multiple of the block size
+pub(crate) fn pad(message: &mut Vec<u32>, block_size: usize, alphabet: &Alphabet) {
+    let filler = alphabet.index_of(alphabet.symbols()[filler(alphabet)]).unwrap();
+    while !message.len().is_multiple_of(block_size) {
+        message.push(filler);
+    }
+}
+
+/// The ranks as the numbers of the columns from 1
+pub(crate) fn to_order(ranks: &[usize]) -> Vec<usize> {
+    ranks.iter().map(|x| x + 1).collect()
+}
+
+fn ranks(values: &[usize], ties: bool) -> Vec<usize> {
+    let mut columns = (0..values.len()).collect::<Vec<usize>>();
+    columns.sort_by_key(|column| (values[*column], *column));
+    let mut ranks = vec![0; values.len()];
+    for (i, column) in columns.iter().enumerate() {
+        ranks[*column] = match i {
+            0 => 0,
+            _ if ties && values[columns[i - 1]] == values[*column] => ranks[columns[i - 1]],
+            _ if ties => ranks[columns[i - 1]] + 1,
+            _ => i,
+        };
+    }
+    ranks
+}
+
+/// The positions of the letters of a message of the given length in the order they are read out of
+/// the table: the columns by their ranks, the columns of the same rank together row by row
+pub(crate) fn reading_order(length: usize, ranks: &[usize]) -> Vec<usize> {
+    let width = ranks.len();
+    let rows = length.div_ceil(width);
+    let mut columns = (0..width).collect::<Vec<usize>>();
+    columns.sort_by_key(|column| (ranks[*column], *column));
+    let mut order = Vec::with_capacity(length);
+    for group in columns.chunk_by(|a, b| ranks[*a] == ranks[*b]) {
+        for row in 0..rows {
+            order.extend(group.iter().map(|column| row * width + column).filter(|x| *x < length));
+        }
+    }
+    order
+}
+
+/// The letters in the order they are read out of the table
+pub(crate) fn transpose<T: Copy>(message: &[T], ranks: &[usize]) -> Vec<T> {
+    permute(message, &reading_order(message.len(), ranks))
+}
+
+/// The letters written back into their places in the table
+pub(crate) fn untranspose<T: Copy + Default>(encrypted_message: &[T], ranks: &[usize]) -> Vec<T> {
+    unpermute(encrypted_message, &reading_order(encrypted_message.len(), ranks))
+}
+
+/// The letters of the message at the positions of the reading order
+pub(crate) fn permute<T: Copy>(message: &[T], order: &[usize]) -> Vec<T> {
+    order.iter().map(|x| message[*x]).collect()
+}
+
+/// The letters read in the reading order written back into their positions
+pub(crate) fn unpermute<T: Copy + Default>(encrypted_message: &[T], order: &[usize]) -> Vec<T> {
+    let mut message = vec![T::default(); encrypted_message.len()];
+    for (letter, position) in encrypted_message.iter().zip(order) {
+        message[*position] = *letter;
+    }
+    message
+}
+
+#[cfg(test)]
+mod transposition_test {
+    use crate::alphabet::Alphabet;
+    use crate::error::CipherError;
+    use crate::transposition::{keyword_ranks, order_ranks, reading_order, transpose, untranspose};
+
+    #[test]
+    fn ranks_work() {
+        let english = Alphabet::english();
+        assert_eq!(keyword_ranks("zebras", &english, false), Ok(vec![5, 2, 1, 3, 0, 4]));
+        assert_eq!(keyword_ranks("tomato", &english, false), Ok(vec![4, 2, 1, 0, 5, 3]));
+        assert_eq!(keyword_ranks("tomato", &english, true), Ok(vec![3, 2, 1, 0, 3, 2]));
+        assert_eq!(keyword_ranks("za", &english.clone().one_based(true), false), Ok(vec![1, 0]));
+        assert_eq!(keyword_ranks("", &Alphabet::english(), false), Err(CipherError::InvalidKey("the key is empty".to_owned())));
+        assert_eq!(order_ranks(&[3, 1, 2], false), Ok(vec![2, 0, 1]));
+        assert_eq!(order_ranks(&[5, 2, 5], true), Ok(vec![1, 0, 1]));
+        assert!(order_ranks(&[3, 1, 3], false).is_err());
+        assert!(order_ranks(&[0, 1], true).is_err());
+    }
+
+    #[test]
+    fn reading_order_works() {
+        // 0 1 2
+        // 3 4
+        assert_eq!(reading_order(5, &[2, 0, 1]), vec![1, 4, 2, 0, 3]);
+        assert_eq!(reading_order(5, &[1, 0, 1]), vec![1, 4, 0, 2, 3]);
+        let message = "WEAREDISCOVERED".chars().collect::<Vec<char>>();
+        let encrypted = transpose(&message, &[3, 1, 0, 2]);
+        assert_eq!(untranspose(&encrypted, &[3, 1, 0, 2]), message);
+    }
+}
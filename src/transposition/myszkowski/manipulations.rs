@@ -0,0 +1,96 @@
+use crate::alphabet::Alphabet;
+use crate::cipher::Cipher;
+use crate::error::CipherError;
+use crate::format::Grouping;
+use crate::transposition::{keyword_ranks, order_ranks, to_order, transpose, untranspose};
+
+/// Columnar transposition where the columns of a repeated letter of the key are read out together,
+/// row by row from left to right, instead of one after another
+pub struct MyszkowskiCipher {
+    // The rank of every column from 0, repeated letters have the same rank
+    ranks: Vec<usize>,
+    alphabet: Alphabet,
+}
+
+impl MyszkowskiCipher {
+    pub fn new(keyword: &str) -> Result<Self, CipherError> {
+        Self::with_alphabet(keyword, Alphabet::english())
+    }
+
+    pub fn with_alphabet(keyword: &str, alphabet: Alphabet) -> Result<Self, CipherError> {
+        Ok(MyszkowskiCipher {
+            ranks: keyword_ranks(keyword, &alphabet, true)?,
+            alphabet,
+        })
+    }
+
+    /// The key as the numbers of the columns in the reading order, the columns read together have
+    /// the same number, like [4, 3, 2, 1, 4, 3] for "tomato"
+    pub fn from_order(order: &[usize]) -> Result<Self, CipherError> {
+        Self::from_order_with_alphabet(order, Alphabet::english())
+    }
+
+    pub fn from_order_with_alphabet(order: &[usize], alphabet: Alphabet) -> Result<Self, CipherError> {
+        Ok(MyszkowskiCipher {
+            ranks: order_ranks(order, true)?,
+            alphabet,
+        })
+    }
+}
+
+impl Cipher for MyszkowskiCipher {
+    /// The numbers of the columns in the reading order
+    type Key = Vec<usize>;
+
+    fn name(&self) -> &'static str {
+        "Myszkowski transposition"
+    }
+
+    fn key(&self) -> Vec<usize> {
+        to_order(&self.ranks)
+    }
+
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn encrypt(&self, message: String) -> String {
+        let encrypted = transpose(&self.alphabet.to_indices(&message), &self.ranks);
+        Grouping::default().format(&self.alphabet.from_indices(&encrypted))
+    }
+
+    fn decrypt(&self, encrypted_message: String) -> Result<String, CipherError> {
+        let decrypted = untranspose(&self.alphabet.to_indices(&encrypted_message), &self.ranks);
+        Ok(self.alphabet.from_indices(&decrypted))
+    }
+}
+
+#[cfg(test)]
+mod myszkowski_test {
+    use crate::cipher::Cipher;
+    use crate::error::CipherError;
+    use crate::transposition::myszkowski::manipulations::MyszkowskiCipher;
+
+    #[test]
+    fn encrypt_works() {
+        let cipher = MyszkowskiCipher::new("tomato").unwrap();
+        assert_eq!(cipher.key(), vec![4, 3, 2, 1, 4, 3]);
+        let encrypted = cipher.encrypt("We are discovered, flee at once".to_owned());
+        assert_eq!(encrypted, "ROFOA CDTED SEEEA CWEIV RLENE");
+        assert_eq!(cipher.decrypt(encrypted), Ok("WEAREDISCOVEREDFLEEATONCE".to_owned()));
+    }
+
+    #[test]
+    fn numeric_order_works() {
+        let cipher = MyszkowskiCipher::from_order(&[7, 5, 3, 1, 7, 5]).unwrap();
+        assert_eq!(cipher.key(), vec![4, 3, 2, 1, 4, 3]);
+        assert_eq!(cipher.encrypt("We are discovered, flee at once".to_owned()), "ROFOA CDTED SEEEA CWEIV RLENE");
+        // Without repeated numbers it's the usual columnar transposition
+        let cipher = MyszkowskiCipher::from_order(&[6, 3, 2, 4, 1, 5]).unwrap();
+        assert_eq!(cipher.encrypt("We are discovered, flee at once".to_owned()), "EVLNA CDTES EAROF ODEEC WIREE");
+        assert_eq!(
+            MyszkowskiCipher::from_order(&[]).err(),
+            Some(CipherError::InvalidKey("the key is empty".to_owned()))
+        );
+    }
+}